@@ -54,17 +54,26 @@ def getsize(filename):
 
 
 def getmtime(filename):
-    """Return the last modification time of a file, reported by os.stat()."""
+    """Return the last modification time of a file, reported by os.stat().
+
+    Use os.stat(filename).st_mtime_ns if full nanosecond precision is needed.
+    """
     return os.stat(filename).st_mtime
 
 
 def getatime(filename):
-    """Return the last access time of a file, reported by os.stat()."""
+    """Return the last access time of a file, reported by os.stat().
+
+    Use os.stat(filename).st_atime_ns if full nanosecond precision is needed.
+    """
     return os.stat(filename).st_atime
 
 
 def getctime(filename):
-    """Return the metadata change time of a file, reported by os.stat()."""
+    """Return the metadata change time of a file, reported by os.stat().
+
+    Use os.stat(filename).st_ctime_ns if full nanosecond precision is needed.
+    """
     return os.stat(filename).st_ctime
 
 
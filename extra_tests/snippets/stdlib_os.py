@@ -10,6 +10,10 @@ assert fd > 0
 
 os.close(fd)
 assert_raises(OSError, lambda: os.read(fd, 10))
+# close(2) errors are reported; closing an already-closed fd raises
+assert_raises(OSError, lambda: os.close(fd))
+# closerange ignores errors from fds that aren't open
+os.closerange(fd, fd + 1)
 assert_raises(FileNotFoundError,
               lambda: os.open('DOES_NOT_EXIST', os.O_RDONLY))
 assert_raises(FileNotFoundError,
@@ -28,6 +32,15 @@ if hasattr(os, "sendfile") and sys.platform.startswith("linux"):
 
     os.lseek(dest_fd, 0, 0)
     assert os.read(src_fd, src_len) == os.read(dest_fd, bytes_sent)
+
+    # offset=None sends from the source fd's current position and
+    # advances it, rather than using (and restoring) an explicit offset
+    os.lseek(src_fd, 0, 0)
+    os.lseek(dest_fd, 0, 0)
+    bytes_sent = os.sendfile(dest_fd, src_fd, None, src_len)
+    assert src_len == bytes_sent
+    assert os.lseek(src_fd, 0, 1) == src_len
+
     os.close(src_fd)
     os.close(dest_fd)
 
@@ -58,6 +71,13 @@ if os.name == "posix":
     os.unsetenv(ENV_KEY)
     assert os.getenv(ENV_KEY) is None
 
+# environ.copy() is a plain dict snapshot, detached from the environment
+env_copy = os.environ.copy()
+assert type(env_copy) is dict
+os.environ[ENV_KEY] = ENV_VALUE
+assert ENV_KEY not in env_copy
+del os.environ[ENV_KEY]
+
 assert os.curdir == "."
 assert os.pardir == ".."
 assert os.extsep == "."
@@ -73,6 +93,90 @@ else:
     assert os.altsep is None
     assert os.pathsep == ":"
 
+# the native posix/nt module carries the same constants, so they are
+# available even when Lib/os.py is not in the loop
+if os.name == "posix":
+    import posix
+
+    assert posix.name == os.name
+    assert posix.curdir == "."
+    assert posix.pardir == ".."
+    assert posix.sep == os.sep
+    assert posix.altsep is None
+    assert posix.extsep == os.extsep
+    assert posix.pathsep == os.pathsep
+    assert posix.defpath == os.defpath
+    assert posix.linesep == os.linesep
+    assert posix.devnull == os.devnull
+
+    # posix.environ is a live view of the environment, so putenv/unsetenv
+    # are reflected immediately (and so is os.environ, which wraps it)
+    os.putenv(ENV_KEY, ENV_VALUE)
+    assert posix.environ[ENV_KEY] == ENV_VALUE.encode()
+    assert posix.environ[ENV_KEY.encode()] == ENV_VALUE.encode()
+    assert ENV_KEY in posix.environ
+    assert os.environ[ENV_KEY] == ENV_VALUE
+    os.unsetenv(ENV_KEY)
+    assert ENV_KEY not in posix.environ
+    assert_raises(KeyError, lambda: posix.environ[ENV_KEY])
+    assert len(posix.environ) == len(list(iter(posix.environ)))
+    posix.environ[ENV_KEY] = ENV_VALUE
+    assert os.getenv(ENV_KEY) == ENV_VALUE
+    del posix.environ[ENV_KEY]
+    assert os.getenv(ENV_KEY) is None
+
+    # environb is the same live view under the bytes-API name
+    posix.environb[ENV_KEY.encode()] = ENV_VALUE.encode()
+    assert posix.environb[ENV_KEY.encode()] == ENV_VALUE.encode()
+    assert os.environb[ENV_KEY.encode()] == ENV_VALUE.encode()
+    del posix.environb[ENV_KEY.encode()]
+    assert ENV_KEY.encode() not in posix.environb
+    assert os.supports_bytes_environ is True
+
+    # the native getenv/getenvb convenience functions, default included
+    assert posix.getenv(ENV_KEY) is None
+    assert posix.getenv(ENV_KEY, 5) == 5
+    assert posix.getenvb(ENV_KEY.encode()) is None
+    os.putenv(ENV_KEY, ENV_VALUE)
+    assert posix.getenv(ENV_KEY) == ENV_VALUE
+    assert posix.getenvb(ENV_KEY.encode()) == ENV_VALUE.encode()
+    assert os.getenvb(ENV_KEY.encode()) == ENV_VALUE.encode()
+    os.unsetenv(ENV_KEY)
+
+    # putenv/unsetenv keep every environ view in sync, bytes one included
+    os.putenv(ENV_KEY, ENV_VALUE)
+    assert ENV_KEY in posix.environ
+    assert ENV_KEY.encode() in os.environb
+    os.unsetenv(ENV_KEY)
+    assert ENV_KEY not in posix.environ
+    assert ENV_KEY.encode() not in os.environb
+
+# os.path.join keeps a trailing separator on the last component
+if os.name == "nt":
+    assert os.path.join("a", "b\\") == "a\\b\\"
+    assert os.path.join("a", "") == "a\\"
+else:
+    assert os.path.join("a", "b/") == "a/b/"
+    assert os.path.join("a/", "b/") == "a/b/"
+    assert os.path.join("a", "") == "a/"
+
+# os.path.isabs accepts bytes paths too
+if os.name == "posix":
+    assert os.path.isabs("/foo")
+    assert os.path.isabs(b"/foo")
+    assert not os.path.isabs("foo")
+    assert not os.path.isabs(b"foo")
+
+# os.path.splitroot
+if os.name == "nt":
+    assert os.path.splitroot("C:\\Users\\Sam") == ("C:", "\\", "Users\\Sam")
+    assert os.path.splitroot("Windows\\notepad") == ("", "", "Windows\\notepad")
+else:
+    assert os.path.splitroot("foo/bar") == ("", "", "foo/bar")
+    assert os.path.splitroot("/foo/bar") == ("", "/", "foo/bar")
+    assert os.path.splitroot("//foo/bar") == ("", "//", "foo/bar")
+    assert os.path.splitroot("///foo/bar") == ("", "/", "//foo/bar")
+
 assert os.fspath("Testing") == "Testing"
 assert os.fspath(b"Testing") == b"Testing"
 assert_raises(TypeError, lambda: os.fspath([1, 2, 3]))
@@ -173,6 +277,18 @@ with TestWithTempDir() as tmpdir:
     symlink_folder = os.path.join(tmpdir, SYMLINK_FOLDER)
     os.symlink(folder, symlink_folder)
 
+    # abspath only makes the path absolute and normalizes it; unlike realpath
+    # it must not resolve symlinks
+    with TestWithTempCurrentDir():
+        os.chdir(tmpdir)
+        abs_link = os.path.abspath(SYMLINK_FILE)
+        assert os.path.basename(abs_link) == SYMLINK_FILE
+        assert os.path.islink(abs_link)
+        assert os.path.realpath(abs_link) == os.path.realpath(fname)
+
+    # chdir on a non-directory must raise NotADirectoryError, not a bare OSError
+    assert_raises(NotADirectoryError, lambda: os.chdir(fname))
+
     names = set()
     paths = set()
     dirs = set()
@@ -183,6 +299,18 @@ with TestWithTempDir() as tmpdir:
     for dir_entry in os.scandir(tmpdir):
         names.add(dir_entry.name)
         paths.add(dir_entry.path)
+        # DirEntry is os.PathLike: path functions accept it directly via
+        # __fspath__
+        assert os.fspath(dir_entry) == dir_entry.path
+        assert os.stat(dir_entry).st_ino == dir_entry.stat().st_ino
+        assert repr(dir_entry) == "<DirEntry {!r}>".format(dir_entry.name)
+        # nothing under a fresh temp dir can be a mount point
+        assert dir_entry.is_mount() is False
+        # repeated stat() calls are served from the per-entry cache
+        assert dir_entry.stat() is dir_entry.stat()
+        assert dir_entry.stat(follow_symlinks=False) is dir_entry.stat(
+            follow_symlinks=False
+        )
         if dir_entry.is_dir():
             assert stat.S_ISDIR(dir_entry.stat().st_mode) is True
             dirs.add(dir_entry.name)
@@ -207,6 +335,27 @@ with TestWithTempDir() as tmpdir:
     assert files_no_symlink == set([FILE_NAME, FILE_NAME2])
     assert symlinks == set([SYMLINK_FILE, SYMLINK_FOLDER])
 
+    with os.scandir(tmpdir) as scandir_iter:
+        assert repr(scandir_iter) == "<ScandirIterator object for '{}'>".format(tmpdir)
+
+    # the directory handle is released as soon as the iterator is closed,
+    # exhausted or dropped, not when the object happens to be collected
+    if os.path.exists("/proc/self/fd"):
+        def open_fds():
+            return len(os.listdir("/proc/self/fd"))
+
+        baseline = open_fds()
+        scandir_iter = os.scandir(tmpdir)
+        assert open_fds() > baseline
+        scandir_iter.close()
+        assert open_fds() == baseline
+        assert_raises(StopIteration, lambda: next(scandir_iter))
+
+        scandir_iter = os.scandir(tmpdir)
+        assert open_fds() > baseline
+        del scandir_iter
+        assert open_fds() == baseline
+
     # Stat
     stat_res = os.stat(fname)
     print(stat_res.st_mode)
@@ -227,6 +376,12 @@ with TestWithTempDir() as tmpdir:
     assert stat_res.st_ctime > 1557500000
     assert stat_res.st_mtime > 1557500000
 
+    # os.path.get*time delegate to os.stat() and follow symlinks
+    assert os.path.getmtime(fname) == stat_res.st_mtime
+    assert os.path.getatime(fname) == stat_res.st_atime
+    assert os.path.getctime(fname) == stat_res.st_ctime
+    assert os.path.getmtime(symlink_file) == os.path.getmtime(fname)
+
     bytes_stats_res = os.stat(fname.encode())
 
     stat_file2 = os.stat(fname2)
@@ -242,6 +397,24 @@ with TestWithTempDir() as tmpdir:
     fd = os.open(fname, os.O_RDWR)
     os.write(fd, CONTENT)
     os.fsync(fd)
+    if hasattr(os, "fdatasync"):
+        os.fdatasync(fd)
+        assert os.fdatasync in os.supports_fd
+
+    # get_terminal_size on a regular file falls back to COLUMNS/LINES
+    old_columns = os.environ.get("COLUMNS")
+    old_lines = os.environ.get("LINES")
+    os.environ["COLUMNS"] = "125"
+    os.environ.pop("LINES", None)
+    size = os.get_terminal_size(fd)
+    assert size.columns == 125
+    assert size.lines == 24
+    os.environ.pop("COLUMNS", None)
+    assert_raises(OSError, lambda: os.get_terminal_size(fd))
+    if old_columns is not None:
+        os.environ["COLUMNS"] = old_columns
+    if old_lines is not None:
+        os.environ["LINES"] = old_lines
 
     # wait a little bit to ensures that the access/modify time is different
     time.sleep(0.1)
@@ -357,10 +530,317 @@ with TestWithTempDir() as tmpdir:
         assert os.path.realpath(os.getcwd()) == os.path.realpath(tmpdir)
         assert os.path.exists(FILE_NAME)
 
+# os.walk: top-down and bottom-up traversal, and onerror callbacks
+with TestWithTempDir() as tmpdir:
+    os.makedirs(os.path.join(tmpdir, "sub", "subsub"))
+    for rel in ("top.txt", os.path.join("sub", "mid.txt"),
+                os.path.join("sub", "subsub", "deep.txt")):
+        open(os.path.join(tmpdir, rel), "w").close()
+
+    walked = []
+    for root, dirs, files in os.walk(tmpdir):
+        walked.append((root, sorted(dirs), sorted(files)))
+    assert walked == [
+        (tmpdir, ["sub"], ["top.txt"]),
+        (os.path.join(tmpdir, "sub"), ["subsub"], ["mid.txt"]),
+        (os.path.join(tmpdir, "sub", "subsub"), [], ["deep.txt"]),
+    ]
+
+    # bottom-up yields the same triples in reverse
+    bottom_up = [(r, sorted(d), sorted(f))
+                 for r, d, f in os.walk(tmpdir, topdown=False)]
+    assert bottom_up == list(reversed(walked))
+
+    # pruning dirnames in top-down mode skips the whole subtree
+    pruned = []
+    for root, dirs, files in os.walk(tmpdir):
+        pruned.append(root)
+        dirs.clear()
+    assert pruned == [tmpdir]
+
+    # errors are reported through onerror instead of aborting the walk
+    errors = []
+    for _ in os.walk(os.path.join(tmpdir, "missing"), onerror=errors.append):
+        pass
+    assert len(errors) == 1
+    assert isinstance(errors[0], OSError)
+
+# os.makedirs: exist_ok semantics
+with TestWithTempDir() as tmpdir:
+    nested = os.path.join(tmpdir, "p1", "p2")
+    os.makedirs(nested)
+    assert os.path.isdir(nested)
+    assert_raises(FileExistsError, lambda: os.makedirs(nested))
+    os.makedirs(nested, exist_ok=True)
+
+    # exist_ok only forgives directories, not files of the same name
+    fpath = os.path.join(tmpdir, "plainfile")
+    open(fpath, "w").close()
+    assert_raises(FileExistsError, lambda: os.makedirs(fpath, exist_ok=True))
+
+    # the native fallback behaves the same way
+    if os.name == "posix":
+        import posix
+
+        nat = os.path.join(tmpdir, "nat", "ive")
+        posix.makedirs(nat)
+        assert os.path.isdir(nat)
+        assert_raises(FileExistsError, lambda: posix.makedirs(nat))
+        posix.makedirs(nat, exist_ok=True)
+
+        # mode is applied (through the umask) to every created component
+        old_umask = os.umask(0o022)
+        try:
+            moded = os.path.join(tmpdir, "mode1", "mode2")
+            posix.makedirs(moded, 0o750)
+            assert stat.S_IMODE(os.stat(moded).st_mode) == 0o750
+            assert stat.S_IMODE(os.stat(os.path.dirname(moded)).st_mode) == 0o750
+        finally:
+            os.umask(old_umask)
+        assert_raises(ValueError,
+                      lambda: posix.makedirs(os.path.join(tmpdir, "badmode"), 0o7777))
+        assert_raises(ValueError,
+                      lambda: posix.makedirs(os.path.join(tmpdir, "badmode"), -1))
+
+# os.removedirs: prunes empty ancestors, stops at the first non-empty one
+with TestWithTempDir() as tmpdir:
+    deepest = os.path.join(tmpdir, "r1", "r2", "r3")
+    os.makedirs(deepest)
+    open(os.path.join(tmpdir, "r1", "keep"), "w").close()
+    os.removedirs(deepest)
+    assert not os.path.exists(os.path.join(tmpdir, "r1", "r2"))
+    assert os.path.isdir(os.path.join(tmpdir, "r1"))
+    assert_raises(OSError, lambda: os.removedirs(os.path.join(tmpdir, "missing")))
+
+    if os.name == "posix":
+        import posix
+
+        os.makedirs(deepest)
+        posix.removedirs(deepest)
+        assert not os.path.exists(os.path.join(tmpdir, "r1", "r2"))
+        assert os.path.isdir(os.path.join(tmpdir, "r1"))
+
+# os.renames: creates the target's parents and prunes the source's
+with TestWithTempDir() as tmpdir:
+    src = os.path.join(tmpdir, "a", "b", "src.txt")
+    os.makedirs(os.path.dirname(src))
+    with open(src, "w") as f:
+        f.write("move me")
+    dst = os.path.join(tmpdir, "c", "d", "dst.txt")
+    os.renames(src, dst)
+    with open(dst) as f:
+        assert f.read() == "move me"
+    assert not os.path.exists(os.path.join(tmpdir, "a"))
+    # a failed rename propagates its OSError
+    assert_raises(OSError, lambda: os.renames(src, dst))
+
+    if os.name == "posix":
+        import posix
+
+        posix.renames(dst, src)
+        with open(src) as f:
+            assert f.read() == "move me"
+        assert not os.path.exists(os.path.join(tmpdir, "c"))
+
+# os.dup / os.dup2
+if os.name == "posix":
+    fd = os.open("README.md", os.O_RDONLY)
+    try:
+        dupped = os.dup(fd)
+        # dup clears the inheritable flag on the duplicate
+        assert os.get_inheritable(dupped) is False
+        # a dup shares the file offset with the original
+        data = os.read(dupped, 4)
+        os.lseek(fd, 0, os.SEEK_SET)
+        assert os.read(fd, 4) == data
+        os.close(dupped)
+
+        target = os.dup(fd)
+        assert os.dup2(fd, target) == target
+        # dup2 leaves the new fd inheritable by default
+        assert os.get_inheritable(target) is True
+        assert os.dup2(fd, target, inheritable=False) == target
+        assert os.get_inheritable(target) is False
+        os.close(target)
+
+        assert_raises(OSError, lambda: os.dup2(-1, target))
+    finally:
+        os.close(fd)
+
+# os.link with dir_fds and follow_symlinks
+with TestWithTempDir() as tmpdir:
+    src = os.path.join(tmpdir, "src.txt")
+    with open(src, "w") as f:
+        f.write("hard")
+    os.link(src, os.path.join(tmpdir, "dst.txt"))
+    assert os.path.samefile(src, os.path.join(tmpdir, "dst.txt"))
+
+    if os.link in os.supports_dir_fd:
+        dfd = os.open(tmpdir, os.O_RDONLY)
+        try:
+            os.link("src.txt", "dst2.txt", src_dir_fd=dfd, dst_dir_fd=dfd)
+        finally:
+            os.close(dfd)
+        assert os.path.samefile(src, os.path.join(tmpdir, "dst2.txt"))
+
+    if os.link in os.supports_follow_symlinks and os.name == "posix":
+        sym = os.path.join(tmpdir, "sym")
+        os.symlink(src, sym)
+        # follow_symlinks=False links the symlink itself
+        lnk = os.path.join(tmpdir, "symlink-copy")
+        os.link(sym, lnk, follow_symlinks=False)
+        assert os.path.islink(lnk)
+        # the default follows it down to the file
+        flat = os.path.join(tmpdir, "followed")
+        os.link(sym, flat)
+        assert not os.path.islink(flat)
+        assert os.path.samefile(src, flat)
+
+# os.rename / os.replace relative to directory fds
+with TestWithTempDir() as tmpdir:
+    src = os.path.join(tmpdir, "moving.txt")
+    with open(src, "w") as f:
+        f.write("contents")
+
+    if os.rename in os.supports_dir_fd:
+        dfd = os.open(tmpdir, os.O_RDONLY)
+        try:
+            os.rename("moving.txt", "moved.txt", src_dir_fd=dfd, dst_dir_fd=dfd)
+            assert not os.path.exists(src)
+            with open(os.path.join(tmpdir, "moved.txt")) as f:
+                assert f.read() == "contents"
+            # replace shares the signature and clobbers an existing target
+            with open(os.path.join(tmpdir, "target.txt"), "w") as f:
+                f.write("old")
+            os.replace("moved.txt", "target.txt", src_dir_fd=dfd, dst_dir_fd=dfd)
+            with open(os.path.join(tmpdir, "target.txt")) as f:
+                assert f.read() == "contents"
+        finally:
+            os.close(dfd)
+
+# os.access with dir_fd / effective_ids / follow_symlinks
+with TestWithTempDir() as tmpdir:
+    fname = os.path.join(tmpdir, "accessible.txt")
+    with open(fname, "w") as f:
+        f.write("x")
+    assert os.access(fname, os.F_OK)
+    assert os.access(fname, os.R_OK | os.W_OK)
+    assert not os.access(os.path.join(tmpdir, "missing"), os.F_OK)
+
+    if os.access in os.supports_dir_fd:
+        dfd = os.open(tmpdir, os.O_RDONLY)
+        try:
+            assert os.access("accessible.txt", os.R_OK, dir_fd=dfd)
+            assert not os.access("missing", os.F_OK, dir_fd=dfd)
+        finally:
+            os.close(dfd)
+
+    if os.access in os.supports_follow_symlinks and os.name == "posix":
+        dangling = os.path.join(tmpdir, "dangling")
+        os.symlink(os.path.join(tmpdir, "missing"), dangling)
+        assert not os.access(dangling, os.F_OK)
+        assert os.access(dangling, os.F_OK, follow_symlinks=False)
+
+    if os.name == "posix":
+        # the effective and real ids agree here, so the answer matches
+        assert os.access(fname, os.R_OK, effective_ids=True)
+
+# os.chmod follow_symlinks
+if os.name == "posix":
+    with TestWithTempDir() as tmpdir:
+        fname = os.path.join(tmpdir, "mode.txt")
+        with open(fname, "w") as f:
+            f.write("x")
+        os.chmod(fname, 0o600)
+        assert stat.S_IMODE(os.stat(fname).st_mode) == 0o600
+
+        sym = os.path.join(tmpdir, "mode-link")
+        os.symlink(fname, sym)
+        # following the link (the default) changes the target's mode
+        os.chmod(sym, 0o644)
+        assert stat.S_IMODE(os.stat(fname).st_mode) == 0o644
+        if sys.platform.startswith("linux"):
+            # Linux can't chmod the symlink itself
+            assert_raises(NotImplementedError,
+                          lambda: os.chmod(sym, 0o644, follow_symlinks=False))
+            assert stat.S_IMODE(os.stat(fname).st_mode) == 0o644
+
+# os.popen runs a shell command attached to a pipe
+if os.name == "posix":
+    with os.popen("echo popen-test") as f:
+        assert f.read() == "popen-test\n"
+    f = os.popen("echo ignored-output")
+    f.read()
+    # a clean exit closes to None...
+    assert f.close() is None
+    # ...and a failure closes to a wait()-style status
+    f = os.popen("exit 3")
+    assert f.read() == ""
+    status = f.close()
+    assert os.waitstatus_to_exitcode(status) == 3
+
+    with TestWithTempDir() as tmpdir:
+        out = os.path.join(tmpdir, "popen-out.txt")
+        f = os.popen("cat > " + out, "w")
+        f.write("written through the shell")
+        assert f.close() is None
+        with open(out) as result:
+            assert result.read() == "written through the shell"
+
+    assert_raises(ValueError, lambda: os.popen("true", "x"))
+    assert_raises(ValueError, lambda: os.popen("true", buffering=0))
+    assert_raises(TypeError, lambda: os.popen(["not", "a", "string"]))
+
+# os.fdopen wraps an existing fd in an io object
+r, w = os.pipe()
+with os.fdopen(w, "w") as wf:
+    wf.write("through the pipe")
+with os.fdopen(r) as rf:
+    assert rf.read() == "through the pipe"
+assert_raises(TypeError, lambda: os.fdopen("notafd"))
+if os.name == "posix":
+    # the native fallback takes the same arguments
+    import posix
+
+    r, w = os.pipe()
+    with posix.fdopen(w, "wb", buffering=0) as wf:
+        wf.write(b"native")
+    with posix.fdopen(r, "rb") as rf:
+        assert rf.read() == b"native"
+
+# os.truncate: works by path and by fd, shrinking and extending
+with TestWithTempDir() as tmpdir:
+    fname = os.path.join(tmpdir, "truncate.txt")
+    with open(fname, "w") as f:
+        f.write("0123456789")
+    os.truncate(fname, 4)
+    assert os.stat(fname).st_size == 4
+    os.truncate(fname, 8)
+    assert os.stat(fname).st_size == 8
+    with open(fname) as f:
+        assert f.read() == "0123" + "\0" * 4
+    fd = os.open(fname, os.O_WRONLY)
+    try:
+        os.truncate(fd, 2)
+    finally:
+        os.close(fd)
+    assert os.stat(fname).st_size == 2
+    assert_raises(FileNotFoundError,
+                  lambda: os.truncate(os.path.join(tmpdir, "missing"), 0))
+    if os.name == "posix" and os.getuid() != 0:
+        # truncate(2) needs write permission on the file, but not an open
+        # for writing; a read-only file must still refuse it
+        os.chmod(fname, 0o444)
+        assert_raises(PermissionError, lambda: os.truncate(fname, 0))
+        os.chmod(fname, 0o644)
+
 # supports
 assert isinstance(os.supports_fd, set)
 assert isinstance(os.supports_dir_fd, set)
 assert isinstance(os.supports_follow_symlinks, set)
+assert os.fstat in os.supports_fd
+assert os.fsync in os.supports_fd
+assert os.ftruncate in os.supports_fd
 
 # get pid
 assert isinstance(os.getpid(), int)
@@ -375,6 +855,11 @@ if "win" not in sys.platform:
     assert isinstance(os.getppid(), int)
     assert isinstance(os.getpgid(os.getpid()), int)
 
+    if hasattr(os, "NGROUPS_MAX"):
+        assert os.NGROUPS_MAX > 0
+        if hasattr(os, "getgroups"):
+            assert len(os.getgroups()) <= os.NGROUPS_MAX
+
     if os.getuid() != 0:
         assert_raises(PermissionError, lambda: os.setgid(42))
         assert_raises(PermissionError, lambda: os.setegid(42))
@@ -384,6 +869,33 @@ if "win" not in sys.platform:
         assert_raises(PermissionError, lambda: os.setreuid(42, 42))
         assert_raises(PermissionError, lambda: os.setresuid(42, 42, 42))
 
+    # extra open(2) flags
+    dfd = os.open("/", os.O_RDONLY | os.O_DIRECTORY)
+    os.close(dfd)
+    with TestWithTempDir() as tmpdir:
+        oflag_target = os.path.join(tmpdir, "target")
+        open(oflag_target, "w").close()
+        # O_DIRECTORY refuses non-directories
+        assert_raises(OSError, lambda: os.open(oflag_target, os.O_RDONLY | os.O_DIRECTORY))
+        # O_NOFOLLOW refuses a final-component symlink
+        oflag_link = os.path.join(tmpdir, "link")
+        os.symlink(oflag_target, oflag_link)
+        assert_raises(OSError, lambda: os.open(oflag_link, os.O_RDONLY | os.O_NOFOLLOW))
+        if hasattr(os, "O_PATH"):
+            fd = os.open(oflag_target, os.O_PATH)
+            os.close(fd)
+    if hasattr(os, "O_TMPFILE"):
+        assert os.O_TMPFILE & os.O_DIRECTORY  # O_TMPFILE embeds O_DIRECTORY
+    if hasattr(os, "O_DIRECT"):
+        assert isinstance(os.O_DIRECT, int)
+    # write-through flags; on linux O_SYNC embeds the data-only O_DSYNC
+    assert isinstance(os.O_SYNC, int)
+    if sys.platform.startswith("linux"):
+        assert os.O_SYNC & os.O_DSYNC == os.O_DSYNC
+    if hasattr(os, "O_NOATIME"):
+        fd = os.open("README.md", os.O_RDONLY | os.O_NOATIME)
+        os.close(fd)
+
     # pty
     a, b = os.openpty()
     assert isinstance(a, int)
@@ -430,6 +942,637 @@ if "win" not in sys.platform:
         os.close(rfd)
         os.close(wfd)
 
+    # fork
+    pid = os.fork()
+    if pid == 0:
+        # child: exit without running any cleanup of the parent's state
+        os._exit(17) if hasattr(os, "_exit") else os.exit(17)
+    assert pid > 0
+    waited_pid, status = os.waitpid(pid, 0)
+    assert waited_pid == pid
+    assert os.WIFEXITED(status)
+    assert os.WEXITSTATUS(status) == 17
+
+    # forkpty: the child's stdout is the pty slave, readable from the master
+    pid, master = os.forkpty()
+    if pid == 0:
+        os.write(1, b"hello from pty")
+        os._exit(0) if hasattr(os, "_exit") else os.exit(0)
+    assert pid > 0
+    data = os.read(master, 1024)
+    assert b"hello from pty" in data
+    os.close(master)
+    os.waitpid(pid, 0)
+
+    # wait3/wait4 return (pid, status, rusage)
+    pid = os.fork()
+    if pid == 0:
+        os._exit(5) if hasattr(os, "_exit") else os.exit(5)
+    waited_pid, status, rusage = os.wait4(pid, 0)
+    assert waited_pid == pid
+    assert os.WIFEXITED(status) and os.WEXITSTATUS(status) == 5
+    # a normal exit neither dumped core nor was continued by SIGCONT
+    assert os.WCOREDUMP(status) is False
+    assert os.WIFCONTINUED(status) is False
+    assert os.waitstatus_to_exitcode(status) == 5
+
+    # killed-by-signal statuses map to the negated signal number
+    import signal
+
+    pid = os.fork()
+    if pid == 0:
+        os.kill(os.getpid(), signal.SIGKILL)
+    _, kill_status = os.waitpid(pid, 0)
+    assert os.waitstatus_to_exitcode(kill_status) == -signal.SIGKILL
+    # a stopped status is not an exit at all
+    assert_raises(ValueError, lambda: os.waitstatus_to_exitcode(0xFF7F))
+    assert isinstance(rusage.ru_utime, float)
+    assert isinstance(rusage.ru_stime, float)
+    assert isinstance(rusage.ru_maxrss, int)
+    assert isinstance(rusage.ru_nswap, int)
+
+    pid = os.fork()
+    if pid == 0:
+        os._exit(6) if hasattr(os, "_exit") else os.exit(6)
+    waited_pid, status, rusage = os.wait3(0)
+    assert waited_pid == pid
+    assert os.WEXITSTATUS(status) == 6
+    assert isinstance(rusage, os.struct_rusage)
+
+    # waitid with the different idtype values
+    if hasattr(os, "waitid"):
+        for idtype, wid in [
+            (os.P_PID, None),
+            (os.P_PGID, os.getpgid(0)),
+            (os.P_ALL, 0),
+        ]:
+            pid = os.fork()
+            if pid == 0:
+                os._exit(3) if hasattr(os, "_exit") else os.exit(3)
+            res = os.waitid(idtype, pid if wid is None else wid, os.WEXITED)
+            assert res.si_pid == pid
+            assert res.si_status == 3
+            if hasattr(os, "CLD_EXITED"):
+                assert res.si_code == os.CLD_EXITED
+
+    # waitpid option and waitid si_code constants
+    assert isinstance(os.WUNTRACED, int)
+    assert isinstance(os.WCONTINUED, int)
+    assert isinstance(os.WNOHANG, int)
+    # the option bits are distinct and usable together
+    assert len({os.WNOHANG, os.WUNTRACED, os.WCONTINUED}) == 3
+
+    # WNOHANG returns (0, 0) while the child is still running
+    r, w = os.pipe()
+    pid = os.fork()
+    if pid == 0:
+        os.read(r, 1)
+        os._exit(0) if hasattr(os, "_exit") else os.exit(0)
+    assert os.waitpid(pid, os.WNOHANG) == (0, 0)
+    os.write(w, b"x")
+    waited_pid, status = os.waitpid(pid, 0)
+    assert waited_pid == pid
+    assert not os.WIFCONTINUED(status)
+    assert not os.WCOREDUMP(status)
+    os.close(r)
+    os.close(w)
+    if hasattr(os, "CLD_EXITED"):
+        cld = {os.CLD_EXITED, os.CLD_KILLED, os.CLD_DUMPED, os.CLD_STOPPED,
+               os.CLD_TRAPPED, os.CLD_CONTINUED}
+        assert len(cld) == 6
+
+    # posix_spawn attributes
+    if hasattr(os, "posix_spawn"):
+        pid = os.posix_spawn("/bin/sh", ["sh", "-c", "exit 17"], {})
+        _, spawn_status = os.waitpid(pid, 0)
+        assert os.waitstatus_to_exitcode(spawn_status) == 17
+
+        # setpgroup=0 puts the child in its own process group
+        pid = os.posix_spawn("/bin/sh", ["sh", "-c", "exit 0"], {}, setpgroup=0)
+        assert os.getpgid(pid) == pid
+        os.waitpid(pid, 0)
+
+        # resetids and setsigmask at least have to round-trip through a spawn
+        pid = os.posix_spawn("/bin/sh", ["sh", "-c", "exit 3"], {},
+                             resetids=True, setsigmask=[signal.SIGUSR1])
+        _, spawn_status = os.waitpid(pid, 0)
+        assert os.waitstatus_to_exitcode(spawn_status) == 3
+
+    # statvfs / fstatvfs
+    if hasattr(os, "statvfs"):
+        res = os.statvfs("/")
+        assert isinstance(res, os.statvfs_result)
+        assert res.f_bsize > 0
+        assert res[0] == res.f_bsize
+        assert res[10] == res.f_fsid
+        fd = os.open("/", os.O_RDONLY)
+        try:
+            fres = os.fstatvfs(fd)
+            assert fres.f_blocks == res.f_blocks
+        finally:
+            os.close(fd)
+
+    # pathconf / fpathconf
+    if hasattr(os, "pathconf"):
+        assert isinstance(os.pathconf_names, dict)
+        assert "PC_NAME_MAX" in os.pathconf_names
+        assert os.pathconf("/", "PC_NAME_MAX") > 0
+        assert os.pathconf("/", os.pathconf_names["PC_NAME_MAX"]) > 0
+        assert_raises(ValueError, lambda: os.pathconf("/", "PC_NOPE"))
+        fd = os.open("/", os.O_RDONLY)
+        try:
+            assert os.fpathconf(fd, "PC_NAME_MAX") > 0
+        finally:
+            os.close(fd)
+
+    # mkfifo
+    if hasattr(os, "mkfifo"):
+        with TestWithTempDir() as tmpdir:
+            fifo_path = os.path.join(tmpdir, "fifo")
+            os.mkfifo(fifo_path)
+            assert stat.S_ISFIFO(os.stat(fifo_path).st_mode)
+            os.unlink(fifo_path)
+            if os.mkfifo in os.supports_dir_fd:
+                dfd = os.open(tmpdir, os.O_RDONLY)
+                try:
+                    os.mkfifo("fifo2", dir_fd=dfd)
+                    assert stat.S_ISFIFO(os.stat(os.path.join(tmpdir, "fifo2")).st_mode)
+                finally:
+                    os.close(dfd)
+
+    # readv / writev scatter-gather I/O
+    if hasattr(os, "writev"):
+        rfd, wfd = os.pipe()
+        try:
+            n = os.writev(wfd, [b"hello", b" ", b"world"])
+            assert n == 11
+            buf1 = bytearray(5)
+            buf2 = bytearray(6)
+            n = os.readv(rfd, [buf1, buf2])
+            assert n == 11
+            assert bytes(buf1) == b"hello"
+            assert bytes(buf2) == b" world"
+        finally:
+            os.close(rfd)
+            os.close(wfd)
+
+    # preadv / pwritev positional scatter-gather I/O
+    if hasattr(os, "pwritev"):
+        with TestWithTempDir() as tmpdir:
+            pv_path = os.path.join(tmpdir, "pv")
+            fd = os.open(pv_path, os.O_RDWR | os.O_CREAT)
+            try:
+                os.write(fd, b"xxxx")
+                assert os.pwritev(fd, [b"ab", b"cd"], 1) == 4
+                b1, b2 = bytearray(2), bytearray(3)
+                assert os.preadv(fd, [b1, b2], 0) == 5
+                assert bytes(b1) == b"xa" and bytes(b2) == b"bcd"
+            finally:
+                os.close(fd)
+        if hasattr(os, "RWF_NOWAIT"):
+            rfd, wfd = os.pipe()
+            try:
+                buf = bytearray(4)
+                assert_raises(
+                    BlockingIOError,
+                    lambda: os.preadv(rfd, [buf], -1, os.RWF_NOWAIT),
+                )
+            finally:
+                os.close(rfd)
+                os.close(wfd)
+
+    # copy_file_range
+    if hasattr(os, "copy_file_range"):
+        with TestWithTempDir() as tmpdir:
+            cfr_src = os.path.join(tmpdir, "cfr_src")
+            cfr_dst = os.path.join(tmpdir, "cfr_dst")
+            with open(cfr_src, "wb") as f:
+                f.write(b"0123456789")
+            sfd = os.open(cfr_src, os.O_RDONLY)
+            dfd = os.open(cfr_dst, os.O_WRONLY | os.O_CREAT)
+            try:
+                assert os.copy_file_range(sfd, dfd, 4, 2, 0) == 4
+                # explicit offsets leave the fd positions untouched
+                assert os.read(sfd, 2) == b"01"
+                assert os.copy_file_range(sfd, dfd, 4) == 4
+            finally:
+                os.close(sfd)
+                os.close(dfd)
+            with open(cfr_dst, "rb") as f:
+                assert f.read() == b"2345"
+
+    # splice
+    if hasattr(os, "splice"):
+        with TestWithTempDir() as tmpdir:
+            sp_path = os.path.join(tmpdir, "sp")
+            with open(sp_path, "wb") as f:
+                f.write(b"spliced data")
+            fd = os.open(sp_path, os.O_RDONLY)
+            rfd, wfd = os.pipe()
+            try:
+                moved = os.splice(fd, wfd, 7)
+                assert moved == 7
+                assert os.read(rfd, 7) == b"spliced"
+                # neither end a pipe -> ValueError
+                fd2 = os.open(sp_path, os.O_RDONLY)
+                fd3 = os.open(sp_path, os.O_WRONLY | os.O_APPEND)
+                try:
+                    assert_raises(ValueError, lambda: os.splice(fd2, fd3, 1))
+                finally:
+                    os.close(fd2)
+                    os.close(fd3)
+            finally:
+                os.close(fd)
+                os.close(rfd)
+                os.close(wfd)
+
+    # fallocate
+    if hasattr(os, "fallocate"):
+        with TestWithTempDir() as tmpdir:
+            fa_path = os.path.join(tmpdir, "fa")
+            fd = os.open(fa_path, os.O_RDWR | os.O_CREAT)
+            try:
+                assert os.fallocate(fd, 0, 0, 4096) is None
+                assert os.stat(fa_path).st_size == 4096
+                if hasattr(os, "FALLOC_FL_KEEP_SIZE"):
+                    os.fallocate(fd, os.FALLOC_FL_KEEP_SIZE, 0, 8192)
+                    assert os.stat(fa_path).st_size == 4096
+            finally:
+                os.close(fd)
+
+    # posix_fadvise / posix_fallocate
+    if hasattr(os, "posix_fallocate"):
+        with TestWithTempDir() as tmpdir:
+            pf_path = os.path.join(tmpdir, "pf")
+            fd = os.open(pf_path, os.O_RDWR | os.O_CREAT)
+            try:
+                assert os.posix_fallocate(fd, 0, 2048) is None
+                assert os.stat(pf_path).st_size == 2048
+                if hasattr(os, "posix_fadvise"):
+                    assert os.posix_fadvise(fd, 0, 0, os.POSIX_FADV_SEQUENTIAL) is None
+                    assert_raises(OSError, lambda: os.posix_fadvise(fd, 0, 0, -1))
+                    # the full set of distinct advice constants is exposed
+                    advice = {os.POSIX_FADV_NORMAL, os.POSIX_FADV_SEQUENTIAL,
+                              os.POSIX_FADV_RANDOM, os.POSIX_FADV_NOREUSE,
+                              os.POSIX_FADV_WILLNEED, os.POSIX_FADV_DONTNEED}
+                    assert len(advice) == 6
+            finally:
+                os.close(fd)
+
+    # getrandom
+    if hasattr(os, "getrandom"):
+        data = os.getrandom(16)
+        assert isinstance(data, bytes)
+        assert len(data) == 16
+        assert os.getrandom(0) == b""
+        assert_raises(ValueError, lambda: os.getrandom(-1))
+        # unlike urandom there is no retry loop, so a non-blocking read of
+        # the blocking pool either succeeds or raises BlockingIOError
+        try:
+            data = os.getrandom(16, os.GRND_RANDOM | os.GRND_NONBLOCK)
+            assert len(data) <= 16
+        except BlockingIOError:
+            pass
+
+    # memfd_create
+    if hasattr(os, "memfd_create"):
+        fd = os.memfd_create("rustpython-test", os.MFD_CLOEXEC)
+        try:
+            assert os.write(fd, b"mem") == 3
+            os.lseek(fd, 0, os.SEEK_SET)
+            assert os.read(fd, 3) == b"mem"
+        finally:
+            os.close(fd)
+
+    # eventfd
+    if hasattr(os, "eventfd"):
+        fd = os.eventfd(3, os.EFD_CLOEXEC | os.EFD_NONBLOCK)
+        try:
+            assert os.eventfd_read(fd) == 3
+            os.eventfd_write(fd, 5)
+            os.eventfd_write(fd, 2)
+            assert os.eventfd_read(fd) == 7
+            assert_raises(BlockingIOError, lambda: os.eventfd_read(fd))
+        finally:
+            os.close(fd)
+
+    # fwalk comes from Lib/os.py once open/stat grow dir_fd and listdir/stat
+    # grow fd support, so mostly sanity-check the plumbing underneath it
+    if hasattr(os, "fwalk"):
+        with TestWithTempDir() as tmpdir:
+            os.makedirs(os.path.join(tmpdir, "a", "b"))
+            for rel in ("f1", os.path.join("a", "f2"), os.path.join("a", "b", "f3")):
+                open(os.path.join(tmpdir, rel), "w").close()
+
+            walked = []
+            for root, dirs, files, rootfd in os.fwalk(tmpdir):
+                assert isinstance(rootfd, int)
+                # the fd must refer to the directory currently being visited
+                assert os.path.samestat(os.stat(rootfd), os.stat(root))
+                assert sorted(os.listdir(rootfd)) == sorted(dirs + files)
+                walked.append((root, sorted(dirs), sorted(files)))
+            assert walked == [
+                (tmpdir, ["a"], ["f1"]),
+                (os.path.join(tmpdir, "a"), ["b"], ["f2"]),
+                (os.path.join(tmpdir, "a", "b"), [], ["f3"]),
+            ]
+
+            bottom_up = [r for r, _, _, _ in os.fwalk(tmpdir, topdown=False)]
+            assert bottom_up == [r for r, _, _ in reversed(walked)]
+
+            fd = os.open(tmpdir, os.O_RDONLY)
+            try:
+                assert [r for r, _, _, _ in os.fwalk("a", dir_fd=fd)] == [
+                    "a",
+                    os.path.join("a", "b"),
+                ]
+            finally:
+                os.close(fd)
+
+    # times
+    if hasattr(os, "times"):
+        t = os.times()
+        assert isinstance(t, os.times_result)
+        assert len(t) == 5
+        assert t.user >= 0.0 and t.system >= 0.0
+        assert t.children_user >= 0.0 and t.children_system >= 0.0
+        assert t.elapsed >= 0.0
+        assert t[0] == t.user
+
+    # register_at_fork: before-hooks run newest-first, after-hooks oldest-first
+    if hasattr(os, "register_at_fork") and hasattr(os, "fork"):
+        assert_raises(TypeError, lambda: os.register_at_fork())
+        assert_raises(TypeError, lambda: os.register_at_fork(before=1))
+
+        events = []
+        r, w = os.pipe()
+        os.register_at_fork(
+            before=lambda: events.append("before1"),
+            after_in_parent=lambda: events.append("parent1"),
+            after_in_child=lambda: os.write(w, b"child1 "),
+        )
+        os.register_at_fork(before=lambda: events.append("before2"))
+        os.register_at_fork(after_in_child=lambda: os.write(w, b"child2"))
+
+        pid = os.fork()
+        if pid == 0:
+            os._exit(0) if hasattr(os, "_exit") else os.exit(0)
+        os.waitpid(pid, 0)
+        os.close(w)
+        assert events == ["before2", "before1", "parent1"]
+        assert os.read(r, 100) == b"child1 child2"
+        os.close(r)
+
+    # st_blocks / st_blksize / st_rdev
+    with TestWithTempDir() as tmpdir:
+        blk_name = os.path.join(tmpdir, "blocks")
+        with open(blk_name, "w") as f:
+            f.write("x" * 1000)
+        st = os.stat(blk_name)
+        assert st.st_blksize > 0
+        # st_blocks counts 512-byte units regardless of st_blksize
+        assert st.st_blocks >= (st.st_size + 511) // 512
+        assert st.st_rdev == 0  # only set for device special files
+        if hasattr(st, "st_birthtime"):
+            assert st.st_birthtime <= st.st_mtime
+            assert abs(st.st_birthtime - st.st_birthtime_ns * 1e-9) < 1e-3
+    try:
+        tty_st = os.stat("/dev/tty")
+    except OSError:
+        pass
+    else:
+        assert tty_st.st_rdev > 0
+
+    # stat_result repr: CPython-style field=value form without private fields
+    st_repr = repr(os.stat("."))
+    assert st_repr.startswith("os.stat_result(st_mode="), st_repr
+    assert "st_ino=" in st_repr and "st_size=" in st_repr
+    assert "__st_atime_int" not in st_repr
+    if hasattr(os, "times"):
+        assert repr(os.times()).startswith("os.times_result(user=")
+    assert repr(os.uname()).startswith("os.uname_result(sysname=")
+
+    # ctermid
+    if hasattr(os, "ctermid"):
+        term = os.ctermid()
+        assert isinstance(term, str)
+        assert term.startswith("/dev/")
+
+    # tcgetpgrp / tcsetpgrp: only meaningful with a controlling terminal
+    if hasattr(os, "tcgetpgrp"):
+        try:
+            tty = os.open("/dev/tty", os.O_RDONLY)
+        except OSError:
+            pass
+        else:
+            try:
+                pgid = os.tcgetpgrp(tty)
+                assert pgid > 0
+                os.tcsetpgrp(tty, pgid)
+            finally:
+                os.close(tty)
+        r, w = os.pipe()
+        try:
+            assert_raises(OSError, lambda: os.tcgetpgrp(r))
+        finally:
+            os.close(r)
+            os.close(w)
+
+    # fchown: chowning to the current owner needs no privilege
+    if hasattr(os, "fchown"):
+        with TestWithTempDir() as tmpdir:
+            fname = os.path.join(tmpdir, "owned")
+            fd = os.open(fname, os.O_CREAT | os.O_WRONLY)
+            try:
+                os.fchown(fd, os.getuid(), os.getgid())
+                os.fchown(fd, -1, -1)
+                os.chown(fd, os.getuid(), -1)
+            finally:
+                os.close(fd)
+            assert_raises(OSError, lambda: os.fchown(-1, os.getuid(), os.getgid()))
+
+    # chflags (BSD only): UF_NODUMP is settable by the file's owner
+    if hasattr(os, "chflags"):
+        assert os.UF_NODUMP == 0x1
+        assert os.UF_IMMUTABLE == 0x2
+        with TestWithTempDir() as tmpdir:
+            fname = os.path.join(tmpdir, "flagged")
+            open(fname, "w").close()
+            os.chflags(fname, os.UF_NODUMP)
+            os.chflags(fname, 0)
+            os.lchflags(fname, 0)
+            assert_raises(FileNotFoundError, lambda: os.chflags(fname + "x", 0))
+
+    # fchmod / lchmod
+    if hasattr(os, "fchmod"):
+        assert os.fchmod in os.supports_fd
+        with TestWithTempDir() as tmpdir:
+            fname = os.path.join(tmpdir, "perm")
+            fd = os.open(fname, os.O_CREAT | os.O_WRONLY, 0o644)
+            try:
+                os.fchmod(fd, 0o421)
+                assert stat.S_IMODE(os.stat(fname).st_mode) == 0o421
+            finally:
+                os.close(fd)
+            assert_raises(OSError, lambda: os.fchmod(-1, 0o644))
+
+            if hasattr(os, "lchmod"):
+                link = os.path.join(tmpdir, "perm_link")
+                os.symlink(fname, link)
+                try:
+                    os.lchmod(link, 0o600)
+                except OSError:
+                    # glibc only emulates AT_SYMLINK_NOFOLLOW on newer versions
+                    pass
+                else:
+                    # the target must be untouched either way
+                    assert stat.S_IMODE(os.stat(fname).st_mode) == 0o421
+
+    # fchdir
+    if hasattr(os, "fchdir"):
+        assert os.fchdir in os.supports_fd
+        cwd = os.getcwd()
+        with TestWithTempDir() as tmpdir:
+            fd = os.open(tmpdir, os.O_RDONLY)
+            try:
+                os.fchdir(fd)
+                assert os.path.samestat(os.stat(os.getcwd()), os.stat(tmpdir))
+            finally:
+                os.chdir(cwd)
+                os.close(fd)
+
+            # any object with a fileno() works too
+            class FakeDirFd:
+                def __init__(self, fd):
+                    self._fd = fd
+
+                def fileno(self):
+                    return self._fd
+
+            fd = os.open(tmpdir, os.O_RDONLY)
+            try:
+                os.fchdir(FakeDirFd(fd))
+                assert os.path.samestat(os.stat(os.getcwd()), os.stat(tmpdir))
+            finally:
+                os.chdir(cwd)
+                os.close(fd)
+
+            regular = os.open(os.path.join(tmpdir, "plain"), os.O_CREAT | os.O_WRONLY)
+            try:
+                assert_raises(NotADirectoryError, lambda: os.fchdir(regular))
+            finally:
+                os.close(regular)
+
+    # nice: raising niceness never needs privilege, and 0 is a no-op probe
+    if hasattr(os, "nice"):
+        before = os.nice(0)
+        assert isinstance(before, int)
+        assert -20 <= before <= 19
+        assert_raises(ValueError, lambda: os.nice(100))
+        assert_raises(ValueError, lambda: os.nice(-100))
+
+    # scheduler interface
+    if hasattr(os, "sched_yield"):
+        os.sched_yield()
+    if hasattr(os, "sched_get_priority_min"):
+        lo = os.sched_get_priority_min(os.SCHED_FIFO)
+        hi = os.sched_get_priority_max(os.SCHED_FIFO)
+        assert lo <= hi
+        assert os.sched_get_priority_min(os.SCHED_OTHER) == 0
+        assert_raises(OSError, lambda: os.sched_get_priority_min(-1))
+    if hasattr(os, "sched_param"):
+        param = os.sched_param(3)
+        assert param.sched_priority == 3
+        assert "sched_priority" in repr(param)
+    if hasattr(os, "sched_getscheduler"):
+        policy = os.sched_getscheduler(0)
+        assert policy >= 0
+        # setting the policy we already have is always permitted
+        os.sched_setscheduler(0, policy, os.sched_param(0))
+        assert_raises(
+            TypeError, lambda: os.sched_setscheduler(0, policy, os.sched_param("x"))
+        )
+
+    if hasattr(os, "sched_getaffinity"):
+        cpus = os.sched_getaffinity(0)
+        assert isinstance(cpus, set)
+        assert len(cpus) >= 1
+        assert all(isinstance(cpu, int) for cpu in cpus)
+        assert len(cpus) <= os.cpu_count()
+        os.sched_setaffinity(0, cpus)
+        os.sched_setaffinity(0, iter(cpus))
+        assert os.sched_getaffinity(0) == cpus
+        assert_raises(ValueError, lambda: os.sched_setaffinity(0, [10 ** 6]))
+        assert_raises(OSError, lambda: os.sched_setaffinity(0, set()))
+
+    # extended attributes: tolerate filesystems mounted without xattr support
+    if hasattr(os, "getxattr"):
+        for xattr_func in (os.getxattr, os.setxattr, os.listxattr, os.removexattr):
+            assert xattr_func in os.supports_fd
+            assert xattr_func in os.supports_follow_symlinks
+        with TestWithTempDir() as tmpdir:
+            fname = os.path.join(tmpdir, "xattr_test")
+            open(fname, "w").close()
+            try:
+                os.setxattr(fname, "user.greeting", b"hello")
+            except OSError:
+                pass
+            else:
+                assert os.getxattr(fname, "user.greeting") == b"hello"
+                assert os.getxattr(fname, b"user.greeting") == b"hello"
+                assert "user.greeting" in os.listxattr(fname)
+                assert_raises(
+                    FileExistsError,
+                    lambda: os.setxattr(
+                        fname, "user.greeting", b"again", os.XATTR_CREATE
+                    ),
+                )
+                os.setxattr(fname, "user.greeting", b"bye", os.XATTR_REPLACE)
+                fd = os.open(fname, os.O_RDONLY)
+                try:
+                    assert os.getxattr(fd, "user.greeting") == b"bye"
+                    assert "user.greeting" in os.listxattr(fd)
+                finally:
+                    os.close(fd)
+                os.removexattr(fname, "user.greeting")
+                assert "user.greeting" not in os.listxattr(fname)
+                assert_raises(
+                    OSError, lambda: os.getxattr(fname, "user.greeting")
+                )
+
+    # makedev / major / minor round-trip
+    if hasattr(os, "makedev"):
+        dev = os.makedev(5, 7)
+        assert isinstance(dev, int)
+        assert os.major(dev) == 5
+        assert os.minor(dev) == 7
+
+    # mknod: the FIFO case needs no privilege, device nodes do
+    if hasattr(os, "mknod"):
+        with TestWithTempDir() as tmpdir:
+            node_path = os.path.join(tmpdir, "node")
+            os.mknod(node_path, stat.S_IFIFO | 0o600)
+            assert stat.S_ISFIFO(os.stat(node_path).st_mode)
+            if os.getuid() != 0:
+                assert_raises(
+                    PermissionError,
+                    lambda: os.mknod(
+                        os.path.join(tmpdir, "dev"), stat.S_IFCHR | 0o600, os.makedev(1, 3)
+                    ) if hasattr(os, "makedev")
+                    else os.mknod(os.path.join(tmpdir, "dev"), stat.S_IFCHR | 0o600, 259),
+                )
+
+    # sysconf / confstr
+    if hasattr(os, "sysconf"):
+        assert os.sysconf("SC_NPROCESSORS_ONLN") >= 1
+        assert os.sysconf(os.sysconf_names["SC_PAGE_SIZE"]) >= 4096
+        assert os.sysconf("SC_OPEN_MAX") > 0
+        assert_raises(ValueError, lambda: os.sysconf("SC_NOPE"))
+    if hasattr(os, "confstr"):
+        path = os.confstr("CS_PATH")
+        assert path is None or "/bin" in path
+        assert_raises(ValueError, lambda: os.confstr("CS_NOPE"))
+
 # os.pipe2
 if sys.platform.startswith('linux') or sys.platform.startswith('freebsd'):
     rfd, wfd = os.pipe2(0)
@@ -498,6 +1641,60 @@ with TestWithTempDir() as tmpdir:
             collected_files = [dir_entry.name for dir_entry in dir_iter]
             assert set(collected_files) == set(expected_files)
 
+# os.mkdir applies the requested mode (modulo the umask)
+if os.name == "posix":
+    with TestWithTempDir() as tmpdir:
+        mode_dir = os.path.join(tmpdir, "mode_dir")
+        old_umask = os.umask(0)
+        try:
+            os.mkdir(mode_dir, 0o700)
+        finally:
+            os.umask(old_umask)
+        assert stat.S_IMODE(os.stat(mode_dir).st_mode) == 0o700
+
+# os.unlink with dir_fd resolves the path relative to the directory fd
+if os.unlink in os.supports_dir_fd:
+    with TestWithTempDir() as tmpdir:
+        unlink_name = os.path.join(tmpdir, "unlink_me")
+        with open(unlink_name, "w") as f:
+            f.write("test")
+        dfd = os.open(tmpdir, os.O_RDONLY)
+        try:
+            os.unlink("unlink_me", dir_fd=dfd)
+        finally:
+            os.close(dfd)
+        assert not os.path.exists(unlink_name)
+
+# closing a scandir iterator from another thread stops the iteration
+with TestWithTempDir() as tmpdir:
+    import threading
+
+    for i in range(100):
+        with open(os.path.join(tmpdir, 'file' + str(i)), 'w') as f:
+            f.write('test')
+
+    dir_iter = os.scandir(tmpdir)
+    closed = [False]
+    yielded_after_close = []
+
+    def iterate():
+        for dir_entry in dir_iter:
+            if closed[0]:
+                yielded_after_close.append(dir_entry.name)
+
+    iter_thread = threading.Thread(target=iterate)
+    iter_thread.start()
+    dir_iter.close()
+    closed[0] = True
+    iter_thread.join()
+
+    # an entry that was already being fetched when close() ran may still be
+    # yielded, but no more than that one
+    assert len(yielded_after_close) <= 1, yielded_after_close
+
+    with assert_raises(StopIteration):
+        next(dir_iter)
+
 # system()
 if "win" not in sys.platform:
     assert os.system('ls') == 0
@@ -527,4 +1724,19 @@ if sys.platform.startswith("win"):
 	assert winver.minor == winver.platform_version[1]
 	assert winver.build == winver.platform_version[2]
 
+	st = os.stat(".")
+	FILE_ATTRIBUTE_DIRECTORY = 0x10
+	assert st.st_file_attributes & FILE_ATTRIBUTE_DIRECTORY
+	# the reparse tag is only nonzero for reparse points
+	assert st.st_reparse_tag == 0
+
+	# hard links share the 64-bit file index exposed as st_ino
+	with TestWithTempDir() as tmpdir:
+		link_a = os.path.join(tmpdir, "a")
+		link_b = os.path.join(tmpdir, "b")
+		open(link_a, "w").close()
+		os.link(link_a, link_b)
+		assert os.stat(link_a).st_ino == os.stat(link_b).st_ino != 0
+		assert os.stat(link_a).st_nlink == 2
+
 
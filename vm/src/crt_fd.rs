@@ -0,0 +1,43 @@
+//! A module implementing an io type backed by the C runtime's file descriptors, i.e. what's
+//! returned from libc::open.
+//!
+//! Not available on windows: there the os module trades in raw HANDLEs rather than CRT fds,
+//! so the operations here would be applied to the wrong kind of value.
+
+use std::io;
+
+fn cvt(ret: i32) -> io::Result<()> {
+    if ret < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// A borrowed C runtime file descriptor.
+///
+/// Operations go through the raw fd rather than through `std::fs::File`, so
+/// they never take ownership of (and therefore never close) the descriptor.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Fd(pub i32);
+
+impl Fd {
+    pub fn close(self) -> io::Result<()> {
+        cvt(unsafe { libc::close(self.0) })
+    }
+
+    pub fn fsync(self) -> io::Result<()> {
+        cvt(unsafe { libc::fsync(self.0) })
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    pub fn fdatasync(self) -> io::Result<()> {
+        // macOS doesn't have fdatasync; the closest equivalent to flushing the
+        // data (but not necessarily the metadata) is the F_FULLFSYNC fcntl
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        return cvt(unsafe { libc::fcntl(self.0, libc::F_FULLFSYNC) });
+        #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+        return cvt(unsafe { libc::fdatasync(self.0) });
+    }
+}
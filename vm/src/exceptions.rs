@@ -789,14 +789,10 @@ impl serde::Serialize for SerializeException<'_> {
             self.exc.traceback().map(Tracebacks)
         };
         struc.serialize_field("traceback", &tbs)?;
-        struc.serialize_field(
-            "cause",
-            &self.exc.cause().as_ref().map(|e| Self::new(self.vm, e)),
-        )?;
-        struc.serialize_field(
-            "context",
-            &self.exc.context().as_ref().map(|e| Self::new(self.vm, e)),
-        )?;
+        let cause = self.exc.cause();
+        struc.serialize_field("cause", &cause.as_ref().map(|e| SerializeException::new(self.vm, e)))?;
+        let context = self.exc.context();
+        struc.serialize_field("context", &context.as_ref().map(|e| SerializeException::new(self.vm, e)))?;
         struc.serialize_field("suppress_context", &self.exc.suppress_context.load())?;
 
         let args = {
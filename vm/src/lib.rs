@@ -48,6 +48,8 @@ mod bytesinner;
 pub mod byteslike;
 pub mod cformat;
 mod coroutine;
+#[cfg(not(windows))]
+pub mod crt_fd;
 mod dictdatatype;
 #[cfg(feature = "rustpython-compiler")]
 pub mod eval;
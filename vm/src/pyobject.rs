@@ -1161,6 +1161,9 @@ pub trait PyStructSequence: StaticType + PyClassImpl + Sized + 'static {
                         .borrow_value()
                         .iter()
                         .zip(Self::FIELD_NAMES.iter().copied())
+                        // fields like stat_result's __st_atime_int stand in for
+                        // unnamed ones; they're positional-only, so hide them
+                        .filter(|(_, name)| !name.starts_with("__"))
                         .map(format_field)
                         .collect();
                     (fields?.join(", "), "")
@@ -10,7 +10,7 @@ cfg_if::cfg_if! {
 }
 use crate::pyobject::PyObjectRef;
 use crate::VirtualMachine;
-pub(crate) use _io::io_open as open;
+pub(crate) use _io::{io_open as open, OpenArgs};
 
 pub(crate) fn make_module(vm: &VirtualMachine) -> PyObjectRef {
     let ctx = &vm.ctx;
@@ -2333,7 +2333,7 @@ mod _io {
 
         #[pymethod]
         fn close(self, vm: &VirtualMachine) -> PyResult<()> {
-            let _ = self.try_resizable(vm)?;
+            let _guard = self.try_resizable(vm)?;
             self.closed.store(true);
             Ok(())
         }
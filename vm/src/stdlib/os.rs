@@ -19,7 +19,7 @@ use crate::builtins::pystr::{PyStr, PyStrRef};
 use crate::builtins::pytype::PyTypeRef;
 use crate::builtins::set::PySet;
 use crate::builtins::tuple::{PyTuple, PyTupleRef};
-use crate::byteslike::PyBytesLike;
+use crate::byteslike::{PyBytesLike, PyRwBytesLike};
 use crate::common::lock::PyRwLock;
 use crate::exceptions::{IntoPyException, PyBaseExceptionRef};
 use crate::function::{ArgumentError, FromArgs, FuncArgs, IntoPyNativeFunc, OptionalArg};
@@ -27,7 +27,7 @@ use crate::pyobject::{
     BorrowValue, Either, IntoPyObject, ItemProtocol, PyObjectRef, PyRef, PyResult,
     PyStructSequence, PyValue, StaticType, TryFromObject, TypeProtocol,
 };
-use crate::slots::PyIter;
+use crate::slots::{Iterable, PyIter};
 use crate::vm::VirtualMachine;
 
 // this is basically what CPython has for Py_off_t; windows uses long long
@@ -48,8 +48,11 @@ impl OutputMode {
         fn inner(mode: OutputMode, path: PathBuf, vm: &VirtualMachine) -> PyResult {
             let path_as_string = |p: PathBuf| {
                 p.into_os_string().into_string().map_err(|_| {
-                    vm.new_unicode_decode_error(
-                        "Can't convert OS path to valid UTF-8 string".into(),
+                    // the path comes from the OS, so a failed conversion isn't a
+                    // decoding error on our side (and on windows the native
+                    // encoding is UTF-16, not UTF-8)
+                    vm.new_value_error(
+                        "Can't convert OS path to a valid Unicode string".to_owned(),
                     )
                 })
             };
@@ -109,6 +112,19 @@ impl PyPathLike {
     }
 }
 
+/// `COLUMNS`/`LINES` fallback for get_terminal_size() when the fd isn't a
+/// terminal. `None` when neither variable is set to something usable.
+fn terminal_size_from_env() -> Option<(usize, usize)> {
+    let var = |name: &str| env::var(name).ok().and_then(|s| s.parse::<usize>().ok());
+    let columns = var("COLUMNS");
+    let lines = var("LINES");
+    if columns.is_none() && lines.is_none() {
+        None
+    } else {
+        Some((columns.unwrap_or(80), lines.unwrap_or(24)))
+    }
+}
+
 fn fs_metadata<P: AsRef<Path>>(path: P, follow_symlink: bool) -> io::Result<fs::Metadata> {
     if follow_symlink {
         fs::metadata(path.as_ref())
@@ -131,10 +147,23 @@ impl TryFromObject for PyPathLike {
                     path: l.borrow_value().into(),
                     mode: OutputMode::String,
                 },
-                ref i @ PyBytes => PyPathLike {
-                    path: bytes_as_osstr(&i, vm)?.to_os_string().into(),
-                    mode: OutputMode::Bytes,
-                },
+                ref i @ PyBytes => {
+                    // CPython on Windows doesn't accept bytes paths for most os
+                    // functions; reject them up front rather than failing later
+                    // in the utf8 -> wide-string conversion.
+                    #[cfg(windows)]
+                    {
+                        let _ = i;
+                        return Err(vm.new_type_error(
+                            "expected str, bytes or os.PathLike object, not 'bytes'".to_owned(),
+                        ));
+                    }
+                    #[cfg(not(windows))]
+                    PyPathLike {
+                        path: bytes_as_osstr(&i, vm)?.to_os_string().into(),
+                        mode: OutputMode::Bytes,
+                    }
+                }
                 _ => return Ok(None),
             });
             Ok(Some(pathlike))
@@ -205,6 +234,8 @@ impl IntoPyException for &'_ io::Error {
                 | Some(errors::EWOULDBLOCK)
                 | Some(errors::EINPROGRESS) => vm.ctx.exceptions.blocking_io_error.clone(),
                 Some(errors::ESRCH) => vm.ctx.exceptions.process_lookup_error.clone(),
+                Some(errors::ENOTDIR) => vm.ctx.exceptions.not_a_directory_error.clone(),
+                Some(errors::EISDIR) => vm.ctx.exceptions.is_a_directory_error.clone(),
                 _ => vm.ctx.exceptions.os_error.clone(),
             },
         };
@@ -341,18 +372,86 @@ mod _os {
     #[pyattr]
     pub(super) const X_OK: u8 = 1;
 
+    // path-manipulation constants; os.path modules written in Python consult
+    // these, so they have to exist even without Lib/os.py in the loop
+    #[pyattr(name = "curdir")]
+    const CURDIR: &str = ".";
+    #[pyattr(name = "pardir")]
+    const PARDIR: &str = "..";
+    #[pyattr(name = "extsep")]
+    const EXTSEP: &str = ".";
+
+    #[cfg(not(windows))]
+    #[pyattr(name = "name")]
+    const NAME: &str = "posix";
+    #[cfg(windows)]
+    #[pyattr(name = "name")]
+    const NAME: &str = "nt";
+
+    #[cfg(not(windows))]
+    #[pyattr(name = "sep")]
+    const SEP: &str = "/";
+    #[cfg(not(windows))]
+    #[pyattr(name = "altsep")]
+    const ALTSEP: Option<&str> = None;
+    #[cfg(not(windows))]
+    #[pyattr(name = "pathsep")]
+    const PATHSEP: &str = ":";
+    #[cfg(not(windows))]
+    #[pyattr(name = "defpath")]
+    const DEFPATH: &str = "/bin:/usr/bin";
+    #[cfg(not(windows))]
+    #[pyattr(name = "linesep")]
+    const LINESEP: &str = "\n";
+    #[cfg(not(windows))]
+    #[pyattr(name = "devnull")]
+    const DEVNULL: &str = "/dev/null";
+
+    #[cfg(windows)]
+    #[pyattr(name = "sep")]
+    const SEP: &str = "\\";
+    #[cfg(windows)]
+    #[pyattr(name = "altsep")]
+    const ALTSEP: Option<&str> = Some("/");
+    #[cfg(windows)]
+    #[pyattr(name = "pathsep")]
+    const PATHSEP: &str = ";";
+    #[cfg(windows)]
+    #[pyattr(name = "defpath")]
+    const DEFPATH: &str = ".;C:\\bin";
+    #[cfg(windows)]
+    #[pyattr(name = "linesep")]
+    const LINESEP: &str = "\r\n";
+    #[cfg(windows)]
+    #[pyattr(name = "devnull")]
+    const DEVNULL: &str = "nul";
+
+    #[cfg(not(windows))]
+    #[pyfunction]
+    fn close(fileno: i64, vm: &VirtualMachine) -> PyResult<()> {
+        match crate::crt_fd::Fd(fileno as i32).close() {
+            // the PEP 475 carve-out: close(2) must never be retried on EINTR
+            // (on Linux the fd is gone either way), so the error is swallowed
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => Ok(()),
+            res => res.map_err(|err| err.into_pyexception(vm)),
+        }
+    }
+
+    // on windows the os module's "fds" are raw HANDLEs (see rust_file), so
+    // closing means dropping the File reconstructed around the handle
+    #[cfg(windows)]
     #[pyfunction]
     fn close(fileno: i64) {
-        //The File type automatically closes when it goes out of scope.
-        //To enable us to close these file descriptors (and hence prevent leaks)
-        //we seek to create the relevant File and simply let it pass out of scope!
         rust_file(fileno);
     }
 
     #[pyfunction]
     fn closerange(fd_low: i64, fd_high: i64) {
         for fileno in fd_low..fd_high {
-            close(fileno);
+            #[cfg(not(windows))]
+            let _ = crate::crt_fd::Fd(fileno as i32).close();
+            #[cfg(windows)]
+            rust_file(fileno);
         }
     }
 
@@ -419,14 +518,43 @@ mod _os {
         Err(vm.new_os_error("os.open not implemented on this platform".to_owned()))
     }
 
-    #[cfg(any(target_os = "linux"))]
+    // Lib/os.py defines its own fdopen on top of io.open; this native one
+    // serves embeddings running without the Python stdlib
     #[pyfunction]
-    fn sendfile(out_fd: i32, in_fd: i32, offset: i64, count: u64, vm: &VirtualMachine) -> PyResult {
-        let mut file_offset = offset;
+    fn fdopen(
+        fd: i32,
+        mode: OptionalArg<PyStrRef>,
+        opts: crate::stdlib::io::OpenArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let mode = mode.into_option();
+        crate::stdlib::io::open(
+            vm.ctx.new_int(fd),
+            mode.as_ref().map(|m| m.borrow_value()),
+            opts,
+            vm,
+        )
+    }
 
-        let res =
-            nix::sys::sendfile::sendfile(out_fd, in_fd, Some(&mut file_offset), count as usize)
-                .map_err(|err| err.into_pyexception(vm))?;
+    #[cfg(any(target_os = "linux"))]
+    #[pyfunction]
+    fn sendfile(
+        out_fd: i32,
+        in_fd: i32,
+        offset: Option<i64>,
+        count: u64,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        // offset=None means "send from the in_fd's current file position",
+        // which maps to a NULL offset pointer for sendfile(2).
+        let res = match offset {
+            Some(offset) => {
+                let mut file_offset = offset;
+                nix::sys::sendfile::sendfile(out_fd, in_fd, Some(&mut file_offset), count as usize)
+            }
+            None => nix::sys::sendfile::sendfile(out_fd, in_fd, None, count as usize),
+        }
+        .map_err(|err| err.into_pyexception(vm))?;
         Ok(vm.ctx.new_int(res as u64))
     }
 
@@ -475,6 +603,63 @@ mod _os {
         Ok(vm.ctx.new_int(written as u64))
     }
 
+    #[cfg(any(target_os = "freebsd"))]
+    #[pyfunction]
+    #[allow(clippy::too_many_arguments)]
+    fn sendfile(
+        out_fd: i32,
+        in_fd: i32,
+        offset: i64,
+        count: u64,
+        headers: OptionalArg<PyObjectRef>,
+        trailers: OptionalArg<PyObjectRef>,
+        flags: OptionalArg<i32>,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let headers = match headers.into_option() {
+            Some(x) => Some(vm.extract_elements::<PyBytesLike>(&x)?),
+            None => None,
+        };
+
+        let headers = headers
+            .as_ref()
+            .map(|v| v.iter().map(|b| b.borrow_value()).collect::<Vec<_>>());
+        let headers = headers
+            .as_ref()
+            .map(|v| v.iter().map(|borrowed| &**borrowed).collect::<Vec<_>>());
+        let headers = headers.as_deref();
+
+        let trailers = match trailers.into_option() {
+            Some(x) => Some(vm.extract_elements::<PyBytesLike>(&x)?),
+            None => None,
+        };
+
+        let trailers = trailers
+            .as_ref()
+            .map(|v| v.iter().map(|b| b.borrow_value()).collect::<Vec<_>>());
+        let trailers = trailers
+            .as_ref()
+            .map(|v| v.iter().map(|borrowed| &**borrowed).collect::<Vec<_>>());
+        let trailers = trailers.as_deref();
+
+        let flags = nix::sys::sendfile::SfFlags::from_bits_truncate(flags.unwrap_or(0));
+
+        // sendfile(2) on FreeBSD reads until EOF when nbytes is 0, which
+        // matches the Python-level count=0 convention.
+        let (res, written) = nix::sys::sendfile::sendfile(
+            in_fd,
+            out_fd,
+            offset,
+            Some(count as usize),
+            headers,
+            trailers,
+            flags,
+            0,
+        );
+        res.map_err(|err| err.into_pyexception(vm))?;
+        Ok(vm.ctx.new_int(written as u64))
+    }
+
     #[pyfunction]
     fn error(message: OptionalArg<PyStrRef>, vm: &VirtualMachine) -> PyResult {
         let msg = message.map_or("".to_owned(), |msg| msg.borrow_value().to_owned());
@@ -491,13 +676,37 @@ mod _os {
         Ok(())
     }
 
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn fdatasync(fd: i64, vm: &VirtualMachine) -> PyResult<()> {
+        // windows has no data-only flush, so fall back to a full commit on
+        // the HANDLE, the same way fsync does
+        #[cfg(windows)]
+        {
+            let file = rust_file(fd);
+            file.sync_all().map_err(|err| err.into_pyexception(vm))?;
+            // Avoid closing the fd
+            raw_file_number(file);
+            Ok(())
+        }
+        #[cfg(not(windows))]
+        crate::crt_fd::Fd(fd as i32)
+            .fdatasync()
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
     #[pyfunction]
     fn read(fd: i64, n: usize, vm: &VirtualMachine) -> PyResult {
         let mut buffer = vec![0u8; n];
         let mut file = rust_file(fd);
-        let n = file
-            .read(&mut buffer)
-            .map_err(|err| err.into_pyexception(vm))?;
+        // retry on EINTR the way CPython does for all blocking syscalls
+        let n = loop {
+            match file.read(&mut buffer) {
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                res => break res,
+            }
+        }
+        .map_err(|err| err.into_pyexception(vm))?;
         buffer.truncate(n);
 
         // Avoid closing the fd
@@ -508,9 +717,13 @@ mod _os {
     #[pyfunction]
     fn write(fd: i64, data: PyBytesLike, vm: &VirtualMachine) -> PyResult {
         let mut file = rust_file(fd);
-        let written = data
-            .with_ref(|b| file.write(b))
-            .map_err(|err| err.into_pyexception(vm))?;
+        let written = loop {
+            match data.with_ref(|b| file.write(b)) {
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                res => break res,
+            }
+        }
+        .map_err(|err| err.into_pyexception(vm))?;
 
         // Avoid closing the fd
         raw_file_number(file);
@@ -519,6 +732,15 @@ mod _os {
 
     #[pyfunction]
     fn remove(path: PyPathLike, dir_fd: DirFd, vm: &VirtualMachine) -> PyResult<()> {
+        #[cfg(unix)]
+        if let Some(fd) = dir_fd.0 {
+            return nix::unistd::unlinkat(
+                Some(fd),
+                path.path.as_os_str(),
+                nix::unistd::UnlinkatFlags::NoRemoveDir,
+            )
+            .map_err(|err| err.into_pyexception(vm));
+        }
         let path = make_path(vm, &path, &dir_fd)?;
         let is_junction = cfg!(windows)
             && fs::symlink_metadata(&path).map_or(false, |meta| {
@@ -536,19 +758,116 @@ mod _os {
     #[pyfunction]
     fn mkdir(
         path: PyPathLike,
-        _mode: OptionalArg<PyIntRef>,
+        mode: OptionalArg<i32>,
         dir_fd: DirFd,
         vm: &VirtualMachine,
     ) -> PyResult<()> {
+        let mode = mode.unwrap_or(0o777);
         let path = make_path(vm, &path, &dir_fd)?;
-        fs::create_dir(path).map_err(|err| err.into_pyexception(vm))
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::DirBuilderExt;
+            fs::DirBuilder::new()
+                .mode(mode as u32)
+                .create(path)
+                .map_err(|err| err.into_pyexception(vm))
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = mode;
+            fs::create_dir(path).map_err(|err| err.into_pyexception(vm))
+        }
+    }
+
+    #[derive(FromArgs)]
+    struct MakedirsArgs {
+        #[pyarg(any)]
+        name: PyPathLike,
+        #[pyarg(any, default = "0o777")]
+        mode: i32,
+        #[pyarg(any, default = "false")]
+        exist_ok: bool,
     }
 
+    // Lib/os.py carries its own makedirs on top of mkdir(); this native one
+    // keeps the same semantics for embeddings running without the Python
+    // stdlib
+    #[pyfunction]
+    fn makedirs(args: MakedirsArgs, vm: &VirtualMachine) -> PyResult<()> {
+        if !(0..=0o777).contains(&args.mode) {
+            return Err(vm.new_value_error("mode must be in range 0o000 to 0o777".to_owned()));
+        }
+        // a recursive DirBuilder is happy with a pre-existing directory, so
+        // the exist_ok=False error has to be raised up front
+        if !args.exist_ok && args.name.path.symlink_metadata().is_ok() {
+            return Err(vm.new_exception_msg(
+                vm.ctx.exceptions.file_exists_error.clone(),
+                format!("File exists: '{}'", args.name.path.display()),
+            ));
+        }
+        let mut builder = fs::DirBuilder::new();
+        builder.recursive(true);
+        #[cfg(unix)]
+        {
+            // every component the recursive create makes gets mkdir'd with
+            // this mode, so each ends up as mode & !umask like CPython's loop
+            use std::os::unix::fs::DirBuilderExt;
+            builder.mode(args.mode as u32);
+        }
+        #[cfg(not(unix))]
+        let _ = args.mode;
+        builder
+            .create(&args.name.path)
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
+    // old spelling, kept for backwards compatibility
     #[pyfunction]
     fn mkdirs(path: PyStrRef, vm: &VirtualMachine) -> PyResult<()> {
         fs::create_dir_all(path.borrow_value()).map_err(|err| err.into_pyexception(vm))
     }
 
+    // Lib/os.py has its own removedirs; as with makedirs, this native twin
+    // covers embeddings running without the Python stdlib
+    #[pyfunction]
+    fn removedirs(path: PyPathLike, vm: &VirtualMachine) -> PyResult<()> {
+        fs::remove_dir(&path.path).map_err(|err| err.into_pyexception(vm))?;
+        // then prune empty ancestors, stopping quietly at the first one that
+        // can't be removed
+        let mut path = path.path.as_path();
+        while let Some(parent) = path.parent() {
+            if parent.as_os_str().is_empty() || fs::remove_dir(parent).is_err() {
+                break;
+            }
+            path = parent;
+        }
+        Ok(())
+    }
+
+    // native twin of Lib/os.py's renames, same caveats as makedirs/removedirs
+    #[pyfunction]
+    fn renames(old: PyPathLike, new: PyPathLike, vm: &VirtualMachine) -> PyResult<()> {
+        if let Some(parent) = new.path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent).map_err(|err| err.into_pyexception(vm))?;
+            }
+        }
+        fs::rename(&old.path, &new.path).map_err(|err| err.into_pyexception(vm))?;
+        if let Some(parent) = old.path.parent() {
+            if !parent.as_os_str().is_empty() {
+                // pruning the emptied source directories is best-effort
+                let _ = removedirs(
+                    PyPathLike {
+                        path: parent.to_owned(),
+                        mode: OutputMode::String,
+                    },
+                    vm,
+                );
+            }
+        }
+        Ok(())
+    }
+
     #[pyfunction]
     fn rmdir(path: PyPathLike, dir_fd: DirFd, vm: &VirtualMachine) -> PyResult<()> {
         let path = make_path(vm, &path, &dir_fd)?;
@@ -571,6 +890,9 @@ mod _os {
         Ok(vm.ctx.new_list(res?))
     }
 
+    // no explicit environ synchronization is needed here or in unsetenv():
+    // on unix, environ is a live view of the environment, and Lib/os.py keeps
+    // the snapshot-based mappings up to date on the other platforms
     #[pyfunction]
     fn putenv(
         key: Either<PyStrRef, PyBytesRef>,
@@ -589,6 +911,18 @@ mod _os {
         Ok(())
     }
 
+    #[pyfunction]
+    fn getenv(
+        key: PyStrRef,
+        default: OptionalArg<PyObjectRef>,
+        vm: &VirtualMachine,
+    ) -> PyObjectRef {
+        match env::var(key.borrow_value()) {
+            Ok(value) => vm.ctx.new_str(value),
+            Err(_) => default.unwrap_or_none(vm),
+        }
+    }
+
     #[pyfunction]
     fn unsetenv(key: Either<PyStrRef, PyBytesRef>, vm: &VirtualMachine) -> PyResult<()> {
         let key: &ffi::OsStr = match key {
@@ -613,6 +947,9 @@ mod _os {
     struct DirEntry {
         entry: fs::DirEntry,
         mode: OutputMode,
+        // one slot each for follow_symlinks=False/True; scanning loops tend to
+        // call stat()/is_dir()/is_file() repeatedly on the same entry
+        stat_cache: PyRwLock<[Option<PyObjectRef>; 2]>,
     }
 
     impl PyValue for DirEntry {
@@ -633,33 +970,36 @@ mod _os {
             self.mode.process_path(self.entry.path(), vm)
         }
 
-        fn perform_on_metadata(
+        /// std's `DirEntry::file_type` reads `d_type` without a syscall where
+        /// the platform fills it in; a full stat is only needed to follow a
+        /// symlink to its target.
+        fn perform_on_file_type(
             &self,
             follow_symlinks: FollowSymlinks,
-            action: fn(fs::Metadata) -> bool,
+            action: fn(fs::FileType) -> bool,
             vm: &VirtualMachine,
         ) -> PyResult<bool> {
-            let meta = fs_metadata(self.entry.path(), follow_symlinks.0)
+            let file_type = self
+                .entry
+                .file_type()
                 .map_err(|err| err.into_pyexception(vm))?;
-            Ok(action(meta))
+            if follow_symlinks.0 && file_type.is_symlink() {
+                let meta = fs_metadata(self.entry.path(), true)
+                    .map_err(|err| err.into_pyexception(vm))?;
+                Ok(action(meta.file_type()))
+            } else {
+                Ok(action(file_type))
+            }
         }
 
         #[pymethod]
         fn is_dir(&self, follow_symlinks: FollowSymlinks, vm: &VirtualMachine) -> PyResult<bool> {
-            self.perform_on_metadata(
-                follow_symlinks,
-                |meta: fs::Metadata| -> bool { meta.is_dir() },
-                vm,
-            )
+            self.perform_on_file_type(follow_symlinks, |ft| ft.is_dir(), vm)
         }
 
         #[pymethod]
         fn is_file(&self, follow_symlinks: FollowSymlinks, vm: &VirtualMachine) -> PyResult<bool> {
-            self.perform_on_metadata(
-                follow_symlinks,
-                |meta: fs::Metadata| -> bool { meta.is_file() },
-                vm,
-            )
+            self.perform_on_file_type(follow_symlinks, |ft| ft.is_file(), vm)
         }
 
         #[pymethod]
@@ -678,7 +1018,15 @@ mod _os {
             follow_symlinks: FollowSymlinks,
             vm: &VirtualMachine,
         ) -> PyResult {
-            stat(
+            // an explicit dir_fd can resolve to a different file, so only
+            // plain lookups hit the cache
+            let cache_slot = dir_fd.0.is_none().then_some(follow_symlinks.0 as usize);
+            if let Some(slot) = cache_slot {
+                if let Some(cached) = self.stat_cache.read()[slot].clone() {
+                    return Ok(cached);
+                }
+            }
+            let result = stat(
                 Either::A(PyPathLike {
                     path: self.entry.path(),
                     mode: OutputMode::String,
@@ -686,22 +1034,93 @@ mod _os {
                 dir_fd,
                 follow_symlinks,
                 vm,
-            )
+            )?;
+            if let Some(slot) = cache_slot {
+                self.stat_cache.write()[slot] = Some(result.clone());
+            }
+            Ok(result)
         }
 
         #[pymethod(magic)]
         fn fspath(&self, vm: &VirtualMachine) -> PyResult {
             self.path(vm)
         }
+
+        #[pymethod(magic)]
+        fn repr(&self, vm: &VirtualMachine) -> PyResult<String> {
+            let name = self.name(vm)?;
+            Ok(format!("<DirEntry {}>", vm.to_repr(&name)?))
+        }
+
+        #[pymethod]
+        fn is_mount(&self, vm: &VirtualMachine) -> PyResult<bool> {
+            let file_type = self
+                .entry
+                .file_type()
+                .map_err(|err| err.into_pyexception(vm))?;
+            // d_type lets us rule out everything that isn't a directory
+            // (including symlinks to directories) without any stat call
+            if !file_type.is_dir() {
+                return Ok(false);
+            }
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::MetadataExt;
+                let path = self.entry.path();
+                let meta = match fs::metadata(&path) {
+                    Ok(meta) => meta,
+                    Err(_) => return Ok(false),
+                };
+                let parent_meta = match fs::metadata(path.join("..")) {
+                    Ok(meta) => meta,
+                    Err(_) => return Ok(false),
+                };
+                // a mount point either sits on a different device than its
+                // parent, or is the same directory as its parent (the root)
+                Ok(meta.dev() != parent_meta.dev() || meta.ino() == parent_meta.ino())
+            }
+            #[cfg(windows)]
+            {
+                use std::os::windows::ffi::OsStrExt;
+                use winapi::um::{fileapi, handleapi, minwinbase, winnt};
+                let path: Vec<u16> = self
+                    .entry
+                    .path()
+                    .as_os_str()
+                    .encode_wide()
+                    .chain(std::iter::once(0))
+                    .collect();
+                let mut find_data = unsafe { std::mem::zeroed::<minwinbase::WIN32_FIND_DATAW>() };
+                let handle = unsafe { fileapi::FindFirstFileW(path.as_ptr(), &mut find_data) };
+                if handle == handleapi::INVALID_HANDLE_VALUE {
+                    return Ok(false);
+                }
+                unsafe { fileapi::FindClose(handle) };
+                // dwReserved0 holds the reparse tag when the reparse-point
+                // attribute is set
+                Ok(
+                    find_data.dwFileAttributes & winnt::FILE_ATTRIBUTE_REPARSE_POINT != 0
+                        && find_data.dwReserved0 == winnt::IO_REPARSE_TAG_MOUNT_POINT,
+                )
+            }
+            #[cfg(not(any(unix, windows)))]
+            {
+                Ok(false)
+            }
+        }
     }
 
     #[pyattr]
     #[pyclass(name = "ScandirIter")]
     #[derive(Debug)]
     struct ScandirIterator {
-        entries: PyRwLock<fs::ReadDir>,
+        // None once the iterator is closed or exhausted, so the directory
+        // handle is released as soon as possible instead of waiting for the
+        // object itself to be dropped
+        entries: PyRwLock<Option<fs::ReadDir>>,
         exhausted: AtomicCell<bool>,
         mode: OutputMode,
+        path: String,
     }
 
     impl PyValue for ScandirIterator {
@@ -714,7 +1133,13 @@ mod _os {
     impl ScandirIterator {
         #[pymethod]
         fn close(&self) {
+            // hold the lock so that a next() running concurrently can't slip in an
+            // extra entry after we're marked exhausted; mark exhausted before the
+            // lock is released so other threads never observe a closed-but-live
+            // iterator
+            let mut entries = self.entries.write();
             self.exhausted.store(true);
+            *entries = None;
         }
 
         #[pymethod(name = "__enter__")]
@@ -726,18 +1151,27 @@ mod _os {
         fn exit(zelf: PyRef<Self>, _args: FuncArgs) {
             zelf.close()
         }
+
+        #[pymethod(magic)]
+        fn repr(&self) -> String {
+            format!("<ScandirIterator object for '{}'>", self.path)
+        }
     }
     impl PyIter for ScandirIterator {
         fn next(zelf: &PyRef<Self>, vm: &VirtualMachine) -> PyResult {
+            let mut entries = zelf.entries.write();
+            // check exhausted under the lock -- close() sets it while holding the
+            // same lock, so we can't race past a close() that already returned
             if zelf.exhausted.load() {
                 return Err(vm.new_stop_iteration());
             }
 
-            match zelf.entries.write().next() {
+            match entries.as_mut().and_then(|entries| entries.next()) {
                 Some(entry) => match entry {
                     Ok(entry) => Ok(DirEntry {
                         entry,
                         mode: zelf.mode,
+                        stat_cache: PyRwLock::default(),
                     }
                     .into_ref(vm)
                     .into_object()),
@@ -745,6 +1179,8 @@ mod _os {
                 },
                 None => {
                     zelf.exhausted.store(true);
+                    // drop the directory handle eagerly, like close() does
+                    *entries = None;
                     Err(vm.new_stop_iteration())
                 }
             }
@@ -758,11 +1194,12 @@ mod _os {
             OptionalArg::Missing => PyPathLike::new_str("."),
         };
 
-        let entries = fs::read_dir(path.path).map_err(|err| err.into_pyexception(vm))?;
+        let entries = fs::read_dir(&path.path).map_err(|err| err.into_pyexception(vm))?;
         Ok(ScandirIterator {
-            entries: PyRwLock::new(entries),
+            entries: PyRwLock::new(Some(entries)),
             exhausted: AtomicCell::new(false),
             mode: path.mode,
+            path: path.path.to_string_lossy().into_owned(),
         }
         .into_ref(vm)
         .into_object())
@@ -789,6 +1226,20 @@ mod _os {
         pub st_atime_ns: BigInt,
         pub st_mtime_ns: BigInt,
         pub st_ctime_ns: BigInt,
+        #[cfg(unix)]
+        pub st_rdev: u64,
+        #[cfg(unix)]
+        pub st_blksize: u64,
+        #[cfg(unix)]
+        pub st_blocks: u64,
+        #[cfg(any(target_os = "macos", target_os = "freebsd"))]
+        pub st_birthtime: f64,
+        #[cfg(any(target_os = "macos", target_os = "freebsd"))]
+        pub st_birthtime_ns: BigInt,
+        #[cfg(windows)]
+        pub st_file_attributes: u32,
+        #[cfg(windows)]
+        pub st_reparse_tag: u32,
     }
 
     #[pyimpl(with(PyStructSequence))]
@@ -799,14 +1250,23 @@ mod _os {
 
         fn from_metadata(meta: fs::Metadata) -> io::Result<Self> {
             let (st_mode, st_ino, st_dev, st_nlink, st_uid, st_gid, ctime);
+            #[cfg(unix)]
+            let (st_rdev, st_blksize, st_blocks);
+            #[cfg(any(target_os = "macos", target_os = "freebsd"))]
+            let (st_birthtime, st_birthtime_ns);
+            #[cfg(windows)]
+            let (st_file_attributes, st_reparse_tag);
             #[cfg(windows)]
             {
+                use std::os::windows::fs::MetadataExt;
                 ctime = meta.created()?;
                 st_ino = 0; // TODO: Not implemented in std::os::windows::fs::MetadataExt.
                 st_dev = 0; // TODO: Not implemented in std::os::windows::fs::MetadataExt.
                 st_nlink = 0; // TODO: Not implemented in std::os::windows::fs::MetadataExt.
                 st_uid = 0; // 0 on windows
                 st_gid = 0; // 0 on windows
+                st_file_attributes = meta.file_attributes();
+                st_reparse_tag = 0; // TODO: not exposed by std::fs::Metadata
             }
             #[cfg(unix)]
             {
@@ -839,6 +1299,15 @@ mod _os {
                 st_nlink = meta.st_nlink();
                 st_uid = meta.st_uid();
                 st_gid = meta.st_gid();
+                st_rdev = meta.st_rdev();
+                st_blksize = meta.st_blksize();
+                st_blocks = meta.st_blocks();
+                #[cfg(any(target_os = "macos", target_os = "freebsd"))]
+                {
+                    let (sec, nsec) = (meta.st_birthtime(), meta.st_birthtime_nsec());
+                    st_birthtime = sec as f64 + nsec as f64 * 1e-9;
+                    st_birthtime_ns = BigInt::from(sec) * 1_000_000_000 + nsec;
+                }
             }
             #[cfg(target_os = "wasi")]
             {
@@ -898,10 +1367,83 @@ mod _os {
                 st_atime_ns: to_nanoseconds_epoch(accessed),
                 st_mtime_ns: to_nanoseconds_epoch(modified),
                 st_ctime_ns: to_nanoseconds_epoch(ctime),
+                #[cfg(unix)]
+                st_rdev,
+                #[cfg(unix)]
+                st_blksize,
+                #[cfg(unix)]
+                st_blocks,
+                #[cfg(any(target_os = "macos", target_os = "freebsd"))]
+                st_birthtime,
+                #[cfg(any(target_os = "macos", target_os = "freebsd"))]
+                st_birthtime_ns,
+                #[cfg(windows)]
+                st_file_attributes,
+                #[cfg(windows)]
+                st_reparse_tag,
             })
         }
     }
 
+    /// Stat an already-open file descriptor without taking ownership of it.
+    ///
+    /// The `File` wrapper is reused for repeated calls on the same fd instead
+    /// of round-tripping through raw_file_number. Stat *results* are
+    /// deliberately not cached -- the file can change between calls.
+    #[cfg(not(windows))]
+    fn fd_metadata(fd: i64) -> io::Result<fs::Metadata> {
+        let file = std::mem::ManuallyDrop::new(rust_file(fd));
+        file.metadata()
+    }
+
+    /// The file index, volume serial and link count aren't reachable through
+    /// `fs::Metadata`, so query them from an open handle.
+    #[cfg(windows)]
+    fn win_file_id(file: &fs::File) -> io::Result<(u64, u64, u64)> {
+        use std::os::windows::io::AsRawHandle;
+        use winapi::um::fileapi::{GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION};
+        let mut info: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+        let ret = unsafe { GetFileInformationByHandle(file.as_raw_handle() as _, &mut info) };
+        if ret == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok((
+            (info.nFileIndexHigh as u64) << 32 | info.nFileIndexLow as u64,
+            info.dwVolumeSerialNumber as u64,
+            info.nNumberOfLinks as u64,
+        ))
+    }
+
+    #[cfg(windows)]
+    fn win_file_id_at(path: &Path, follow_symlinks: bool) -> io::Result<(u64, u64, u64)> {
+        use std::os::windows::fs::OpenOptionsExt;
+        // BACKUP_SEMANTICS lets us open directories; OPEN_REPARSE_POINT makes
+        // the handle refer to a symlink itself rather than its target
+        const FILE_FLAG_BACKUP_SEMANTICS: u32 = 0x0200_0000;
+        const FILE_FLAG_OPEN_REPARSE_POINT: u32 = 0x0020_0000;
+        let mut flags = FILE_FLAG_BACKUP_SEMANTICS;
+        if !follow_symlinks {
+            flags |= FILE_FLAG_OPEN_REPARSE_POINT;
+        }
+        let file = OpenOptions::new()
+            .access_mode(0) // any handle will do; no read access needed
+            .custom_flags(flags)
+            .open(path)?;
+        win_file_id(&file)
+    }
+
+    #[cfg(windows)]
+    impl StatResult {
+        fn set_file_id(&mut self, id: io::Result<(u64, u64, u64)>) {
+            // leave the fields zeroed rather than failing the whole stat call
+            if let Ok((st_ino, st_dev, st_nlink)) = id {
+                self.st_ino = st_ino;
+                self.st_dev = st_dev;
+                self.st_nlink = st_nlink;
+            }
+        }
+    }
+
     #[pyfunction]
     fn stat(
         file: Either<PyPathLike, i64>,
@@ -910,12 +1452,45 @@ mod _os {
         vm: &VirtualMachine,
     ) -> PyResult {
         let meta = match file {
-            Either::A(path) => fs_metadata(make_path(vm, &path, &dir_fd)?, follow_symlinks.0),
+            Either::A(path) => {
+                #[cfg(windows)]
+                if let Some(fd) = dir_fd.0 {
+                    if !path.path.is_absolute() {
+                        return platform::stat_at(fd.into(), &path.path, follow_symlinks.0)
+                            .and_then(StatResult::from_metadata)
+                            .map(|stat| stat.into_obj(vm))
+                            .map_err(|err| err.into_pyexception(vm));
+                    }
+                }
+                let path = make_path(vm, &path, &dir_fd)?;
+                #[cfg(windows)]
+                {
+                    return fs_metadata(&path, follow_symlinks.0)
+                        .and_then(StatResult::from_metadata)
+                        .map(|mut stat| {
+                            stat.set_file_id(win_file_id_at(Path::new(&*path), follow_symlinks.0));
+                            stat.into_obj(vm)
+                        })
+                        .map_err(|err| err.into_pyexception(vm));
+                }
+                #[cfg(not(windows))]
+                fs_metadata(path, follow_symlinks.0)
+            }
             Either::B(fno) => {
-                let file = rust_file(fno);
-                let res = file.metadata();
-                raw_file_number(file);
-                res
+                #[cfg(windows)]
+                {
+                    let file = std::mem::ManuallyDrop::new(rust_file(fno));
+                    return file
+                        .metadata()
+                        .and_then(StatResult::from_metadata)
+                        .map(|mut stat| {
+                            stat.set_file_id(win_file_id(&file));
+                            stat.into_obj(vm)
+                        })
+                        .map_err(|err| err.into_pyexception(vm));
+                }
+                #[cfg(not(windows))]
+                fd_metadata(fno)
             }
         };
         meta.and_then(StatResult::from_metadata)
@@ -960,19 +1535,79 @@ mod _os {
         path.mode.process_path(path.path, vm)
     }
 
+    #[derive(FromArgs)]
+    struct RenameArgs {
+        #[pyarg(positional)]
+        src: PyPathLike,
+        #[pyarg(positional)]
+        dst: PyPathLike,
+        #[pyarg(named, default)]
+        src_dir_fd: Option<i32>,
+        #[pyarg(named, default)]
+        dst_dir_fd: Option<i32>,
+    }
+
     #[pyfunction]
-    fn rename(src: PyPathLike, dst: PyPathLike, vm: &VirtualMachine) -> PyResult<()> {
-        fs::rename(src.path, dst.path).map_err(|err| err.into_pyexception(vm))
+    fn rename(args: RenameArgs, vm: &VirtualMachine) -> PyResult<()> {
+        if args.src_dir_fd.is_some() || args.dst_dir_fd.is_some() {
+            #[cfg(unix)]
+            {
+                use std::os::unix::ffi::OsStrExt;
+                let cstring = |path: &PyPathLike| {
+                    ffi::CString::new(path.path.as_os_str().as_bytes())
+                        .map_err(|_| vm.new_value_error("embedded null character".to_owned()))
+                };
+                let src = cstring(&args.src)?;
+                let dst = cstring(&args.dst)?;
+                let ret = unsafe {
+                    libc::renameat(
+                        args.src_dir_fd.unwrap_or(libc::AT_FDCWD),
+                        src.as_ptr(),
+                        args.dst_dir_fd.unwrap_or(libc::AT_FDCWD),
+                        dst.as_ptr(),
+                    )
+                };
+                if ret < 0 {
+                    return Err(errno_err(vm));
+                }
+                return Ok(());
+            }
+            #[cfg(not(unix))]
+            return Err(vm.new_not_implemented_error(
+                "rename: src_dir_fd and dst_dir_fd unavailable on this platform".to_owned(),
+            ));
+        }
+        fs::rename(args.src.path, args.dst.path).map_err(|err| err.into_pyexception(vm))
     }
 
     #[pyfunction]
     fn getpid(vm: &VirtualMachine) -> PyObjectRef {
+        // std::process::id() widens to u32, which would mangle a pid_t on
+        // 32-bit platforms; report the C type like getppid already does
+        #[cfg(any(unix, windows))]
+        let pid = unsafe { libc::getpid() };
+        #[cfg(not(any(unix, windows)))]
         let pid = std::process::id();
         vm.ctx.new_int(pid)
     }
 
     #[pyfunction]
     fn cpu_count(vm: &VirtualMachine) -> PyObjectRef {
+        // the CPUs usable by the current process bound multiprocessing's
+        // default worker count, so prefer the affinity mask when we can read it
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        {
+            let mut cpuset: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+            let ret = unsafe {
+                libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut cpuset)
+            };
+            if ret == 0 {
+                let count = unsafe { libc::CPU_COUNT(&cpuset) };
+                if count > 0 {
+                    return vm.ctx.new_int(count);
+                }
+            }
+        }
         let cpu_count = num_cpus::get();
         vm.ctx.new_int(cpu_count)
     }
@@ -1035,21 +1670,59 @@ mod _os {
         }
     }
 
-    #[pyfunction]
-    fn link(src: PyPathLike, dst: PyPathLike, vm: &VirtualMachine) -> PyResult<()> {
-        fs::hard_link(src.path, dst.path).map_err(|err| err.into_pyexception(vm))
-    }
-
     #[derive(FromArgs)]
-    struct UtimeArgs {
-        #[pyarg(any)]
-        path: PyPathLike,
-        #[pyarg(any, default)]
-        times: Option<PyTupleRef>,
+    struct LinkArgs {
+        #[pyarg(positional)]
+        src: PyPathLike,
+        #[pyarg(positional)]
+        dst: PyPathLike,
         #[pyarg(named, default)]
-        ns: Option<PyTupleRef>,
+        src_dir_fd: Option<i32>,
+        #[pyarg(named, default)]
+        dst_dir_fd: Option<i32>,
         #[pyarg(flatten)]
-        dir_fd: DirFd,
+        follow_symlinks: FollowSymlinks,
+    }
+
+    #[cfg(all(unix, not(target_os = "redox")))]
+    #[pyfunction]
+    fn link(args: LinkArgs, vm: &VirtualMachine) -> PyResult<()> {
+        let flag = if args.follow_symlinks.0 {
+            nix::unistd::LinkatFlags::SymlinkFollow
+        } else {
+            nix::unistd::LinkatFlags::NoSymlinkFollow
+        };
+        nix::unistd::linkat(
+            args.src_dir_fd,
+            &*args.src.path,
+            args.dst_dir_fd,
+            &*args.dst.path,
+            flag,
+        )
+        .map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(not(all(unix, not(target_os = "redox"))))]
+    #[pyfunction]
+    fn link(args: LinkArgs, vm: &VirtualMachine) -> PyResult<()> {
+        if args.src_dir_fd.is_some() || args.dst_dir_fd.is_some() {
+            return Err(vm.new_not_implemented_error(
+                "link: src_dir_fd and dst_dir_fd unavailable on this platform".to_owned(),
+            ));
+        }
+        fs::hard_link(args.src.path, args.dst.path).map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[derive(FromArgs)]
+    struct UtimeArgs {
+        #[pyarg(any)]
+        path: PyPathLike,
+        #[pyarg(any, default)]
+        times: Option<PyTupleRef>,
+        #[pyarg(named, default)]
+        ns: Option<PyTupleRef>,
+        #[pyarg(flatten)]
+        dir_fd: DirFd,
         #[pyarg(flatten)]
         follow_symlinks: FollowSymlinks,
     }
@@ -1263,18 +1936,45 @@ mod _os {
             return ftruncate(fd, length, vm);
         }
         let path = PyPathLike::try_from_object(vm, path)?;
-        // TODO: just call libc::truncate() on POSIX
-        let f = OpenOptions::new()
-            .write(true)
-            .open(&path)
-            .map_err(|e| e.into_pyexception(vm))?;
-        f.set_len(length as u64)
-            .map_err(|e| e.into_pyexception(vm))?;
-        drop(f);
-        Ok(())
+        #[cfg(unix)]
+        {
+            // truncate(2) only needs write permission on the file itself, and
+            // avoids racing against a rename between an open and a set_len
+            use std::os::unix::ffi::OsStrExt;
+            let path = ffi::CString::new(path.path.as_os_str().as_bytes())
+                .map_err(|_| vm.new_value_error("embedded null character".to_owned()))?;
+            let ret = unsafe { libc::truncate(path.as_ptr(), length as libc::off_t) };
+            if ret < 0 {
+                return Err(errno_err(vm));
+            }
+            Ok(())
+        }
+        #[cfg(not(unix))]
+        {
+            let f = OpenOptions::new()
+                .write(true)
+                .open(&path)
+                .map_err(|e| e.into_pyexception(vm))?;
+            f.set_len(length as u64)
+                .map_err(|e| e.into_pyexception(vm))?;
+            drop(f);
+            Ok(())
+        }
     }
 
-    #[cfg(unix)]
+    // every unix libc with getloadavg(3); android and redox lack it
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "emscripten",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "dragonfly",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "solaris",
+        target_os = "illumos",
+    ))]
     #[pyfunction]
     fn getloadavg(vm: &VirtualMachine) -> PyResult<(f64, f64, f64)> {
         let mut loadavg = [0f64; 3];
@@ -1301,6 +2001,19 @@ mod _os {
     #[pyimpl(with(PyStructSequence))]
     impl PyTerminalSize {}
 
+    #[pyattr]
+    #[pyclass(module = "os", name = "times_result")]
+    #[derive(Debug, PyStructSequence)]
+    pub(super) struct TimesResult {
+        pub user: f64,
+        pub system: f64,
+        pub children_user: f64,
+        pub children_system: f64,
+        pub elapsed: f64,
+    }
+    #[pyimpl(with(PyStructSequence))]
+    impl TimesResult {}
+
     pub(super) fn support_funcs(vm: &VirtualMachine) -> Vec<SupportFunc> {
         let mut supports = super::platform::support_funcs(vm);
         supports.extend(vec![
@@ -1310,27 +2023,55 @@ mod _os {
                 "access",
                 platform::access,
                 Some(false),
-                Some(false),
-                None,
+                Some(cfg!(any(
+                    target_os = "linux",
+                    target_os = "macos",
+                    target_os = "ios",
+                    target_os = "freebsd",
+                    target_os = "dragonfly",
+                    target_os = "netbsd",
+                    target_os = "openbsd"
+                ))),
+                Some(cfg!(any(
+                    target_os = "linux",
+                    target_os = "macos",
+                    target_os = "ios",
+                    target_os = "freebsd",
+                    target_os = "dragonfly",
+                    target_os = "netbsd",
+                    target_os = "openbsd"
+                ))),
             ),
             SupportFunc::new(vm, "chdir", chdir, Some(false), None, None),
             // chflags Some, None Some
             SupportFunc::new(vm, "listdir", listdir, Some(true), None, None),
+            SupportFunc::new(
+                vm,
+                "link",
+                link,
+                Some(false),
+                Some(cfg!(all(unix, not(target_os = "redox")))),
+                Some(cfg!(all(unix, not(target_os = "redox")))),
+            ),
             SupportFunc::new(vm, "mkdir", mkdir, Some(false), Some(false), None),
             // mkfifo Some Some None
             // mknod Some Some None
             // pathconf Some None None
             SupportFunc::new(vm, "readlink", readlink, Some(false), Some(false), None),
-            SupportFunc::new(vm, "remove", remove, Some(false), Some(false), None),
-            SupportFunc::new(vm, "rename", rename, Some(false), Some(false), None),
-            SupportFunc::new(vm, "replace", rename, Some(false), Some(false), None), // TODO: Fix replace
+            SupportFunc::new(vm, "remove", remove, Some(false), Some(cfg!(unix)), None),
+            SupportFunc::new(vm, "rename", rename, Some(false), Some(cfg!(unix)), None),
+            SupportFunc::new(vm, "replace", rename, Some(false), Some(cfg!(unix)), None), // TODO: Fix replace
             SupportFunc::new(vm, "rmdir", rmdir, Some(false), Some(false), None),
             SupportFunc::new(vm, "scandir", scandir, Some(false), None, None),
             SupportFunc::new(vm, "stat", stat, Some(true), Some(true), Some(true)),
             SupportFunc::new(vm, "fstat", stat, Some(true), Some(true), Some(true)),
+            SupportFunc::new(vm, "fsync", fsync, Some(true), None, None),
+            #[cfg(not(target_os = "redox"))]
+            SupportFunc::new(vm, "fdatasync", fdatasync, Some(true), None, None),
+            SupportFunc::new(vm, "ftruncate", ftruncate, Some(true), None, None),
             SupportFunc::new(vm, "symlink", platform::symlink, None, Some(false), None),
             // truncate Some None None
-            SupportFunc::new(vm, "unlink", remove, Some(false), Some(false), None),
+            SupportFunc::new(vm, "unlink", remove, Some(false), Some(cfg!(unix)), None),
             #[cfg(not(target_os = "wasi"))]
             SupportFunc::new(
                 vm,
@@ -1463,12 +2204,65 @@ mod posix {
     #[cfg(not(any(target_os = "redox", target_os = "freebsd")))]
     #[pyattr]
     use libc::O_DSYNC;
+    #[cfg(not(target_os = "redox"))]
+    #[pyattr]
+    use libc::O_SYNC;
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "emscripten"))]
+    #[pyattr]
+    use libc::O_NOATIME;
     #[pyattr]
-    use libc::{O_CLOEXEC, O_NONBLOCK, WNOHANG};
+    use libc::{O_CLOEXEC, O_DIRECTORY, O_NOFOLLOW, O_NONBLOCK, WCONTINUED, WNOHANG, WUNTRACED};
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "emscripten"))]
+    #[pyattr]
+    use libc::{O_PATH, O_TMPFILE};
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "emscripten",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "netbsd"
+    ))]
+    #[pyattr]
+    use libc::O_DIRECT;
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+    #[pyattr]
+    use libc::{P_ALL, P_PGID, P_PID, WEXITED, WNOWAIT, WSTOPPED};
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "emscripten",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    #[pyattr]
+    use libc::{
+        CLD_CONTINUED, CLD_DUMPED, CLD_EXITED, CLD_KILLED, CLD_STOPPED, CLD_TRAPPED,
+    };
     #[cfg(not(target_os = "redox"))]
     #[pyattr]
     use libc::{O_NDELAY, O_NOCTTY};
 
+    // NGROUPS_MAX as of the platform's limits.h; libc doesn't expose it
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "emscripten"))]
+    #[pyattr]
+    const NGROUPS_MAX: i32 = 65536;
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    #[pyattr]
+    const NGROUPS_MAX: i32 = 16;
+    #[cfg(any(target_os = "freebsd", target_os = "dragonfly"))]
+    #[pyattr]
+    const NGROUPS_MAX: i32 = 1023;
+
     #[pyattr]
     const EX_OK: i8 = exitcode::OK as i8;
     #[pyattr]
@@ -1502,6 +2296,16 @@ mod posix {
     #[pyattr]
     const EX_CONFIG: i8 = exitcode::CONFIG as i8;
 
+    #[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos"))]
+    #[pyattr]
+    use libc::{
+        POSIX_SPAWN_RESETIDS, POSIX_SPAWN_SETPGROUP, POSIX_SPAWN_SETSIGDEF, POSIX_SPAWN_SETSIGMASK,
+    };
+    // not in libc yet; glibc's value, honored by linux >= 4.7
+    #[cfg(target_os = "linux")]
+    #[pyattr]
+    const POSIX_SPAWN_SETSID: i32 = 0x80;
+
     #[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos"))]
     #[pyattr]
     const POSIX_SPAWN_OPEN: i32 = PosixSpawnFileActionIdentifier::Open as i32;
@@ -1550,16 +2354,39 @@ mod posix {
     pub(super) fn convert_nix_errno(vm: &VirtualMachine, errno: Errno) -> PyTypeRef {
         match errno {
             Errno::EPERM => vm.ctx.exceptions.permission_error.clone(),
+            Errno::EAGAIN => vm.ctx.exceptions.blocking_io_error.clone(),
+            Errno::EEXIST => vm.ctx.exceptions.file_exists_error.clone(),
+            Errno::ENOTDIR => vm.ctx.exceptions.not_a_directory_error.clone(),
             _ => vm.ctx.exceptions.os_error.clone(),
         }
     }
 
+    // manual permission checks for the platforms without faccessat(2); the
+    // faccessat-based access() below doesn't need any of this
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
     struct Permissions {
         is_readable: bool,
         is_writable: bool,
         is_executable: bool,
     }
 
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
     fn get_permissions(mode: u32) -> Permissions {
         Permissions {
             is_readable: mode & 4 != 0,
@@ -1568,6 +2395,15 @@ mod posix {
         }
     }
 
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
     fn get_right_permission(
         mode: u32,
         file_owner: Uid,
@@ -1594,26 +2430,16 @@ mod posix {
         }
     }
 
-    #[cfg(any(target_os = "macos", target_os = "ios"))]
-    fn getgroups() -> nix::Result<Vec<Gid>> {
-        use libc::{c_int, gid_t};
-        use std::ptr;
-        let ret = unsafe { libc::getgroups(0, ptr::null_mut()) };
-        let mut groups = Vec::<Gid>::with_capacity(Errno::result(ret)? as usize);
-        let ret = unsafe {
-            libc::getgroups(
-                groups.capacity() as c_int,
-                groups.as_mut_ptr() as *mut gid_t,
-            )
-        };
-
-        Errno::result(ret).map(|s| {
-            unsafe { groups.set_len(s as usize) };
-            groups
-        })
-    }
-
-    #[cfg(not(any(target_os = "macos", target_os = "ios", target_os = "redox")))]
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "redox"
+    )))]
     use nix::unistd::getgroups;
 
     #[cfg(target_os = "redox")]
@@ -1621,38 +2447,104 @@ mod posix {
         Err(nix::Error::UnsupportedOperation)
     }
 
-    #[pyfunction]
-    pub(super) fn access(path: PyPathLike, mode: u8, vm: &VirtualMachine) -> PyResult<bool> {
-        use std::os::unix::fs::MetadataExt;
+    #[derive(FromArgs)]
+    pub(super) struct AccessArgs {
+        #[pyarg(positional)]
+        path: PyPathLike,
+        #[pyarg(positional)]
+        mode: u8,
+        #[pyarg(flatten)]
+        dir_fd: DirFd,
+        #[pyarg(named, default = "false")]
+        effective_ids: bool,
+        #[pyarg(flatten)]
+        follow_symlinks: FollowSymlinks,
+    }
 
-        let flags = AccessFlags::from_bits(mode).ok_or_else(|| {
+    #[pyfunction]
+    pub(super) fn access(args: AccessArgs, vm: &VirtualMachine) -> PyResult<bool> {
+        let flags = AccessFlags::from_bits(args.mode).ok_or_else(|| {
             vm.new_value_error(
             "One of the flags is wrong, there are only 4 possibilities F_OK, R_OK, W_OK and X_OK"
                 .to_owned(),
         )
         })?;
 
-        let metadata = fs::metadata(&path.path);
-
-        // if it's only checking for F_OK
-        if flags == AccessFlags::F_OK {
-            return Ok(metadata.is_ok());
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "freebsd",
+            target_os = "dragonfly",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        {
+            // the kernel does the whole check in one faccessat(2) call, so
+            // there's no window for the permissions to change under us
+            use std::os::unix::ffi::OsStrExt;
+            let path = ffi::CString::new(args.path.path.as_os_str().as_bytes())
+                .map_err(|_| vm.new_value_error("embedded null character".to_owned()))?;
+            let mut at_flags = 0;
+            if args.effective_ids {
+                at_flags |= libc::AT_EACCESS;
+            }
+            if !args.follow_symlinks.0 {
+                at_flags |= libc::AT_SYMLINK_NOFOLLOW;
+            }
+            let ret = unsafe {
+                libc::faccessat(
+                    args.dir_fd.0.unwrap_or(libc::AT_FDCWD),
+                    path.as_ptr(),
+                    flags.bits() as i32,
+                    at_flags,
+                )
+            };
+            Ok(ret == 0)
         }
 
-        let metadata = metadata.map_err(|err| err.into_pyexception(vm))?;
+        #[cfg(not(any(
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "freebsd",
+            target_os = "dragonfly",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        )))]
+        {
+            use std::os::unix::fs::MetadataExt;
+
+            if args.dir_fd.0.is_some() || args.effective_ids || !args.follow_symlinks.0 {
+                return Err(vm.new_not_implemented_error(
+                    "access: dir_fd, effective_ids and follow_symlinks unavailable on this \
+                     platform"
+                        .to_owned(),
+                ));
+            }
+
+            let metadata = fs::metadata(&args.path.path);
 
-        let user_id = metadata.uid();
-        let group_id = metadata.gid();
-        let mode = metadata.mode();
+            // if it's only checking for F_OK
+            if flags == AccessFlags::F_OK {
+                return Ok(metadata.is_ok());
+            }
 
-        let perm = get_right_permission(mode, Uid::from_raw(user_id), Gid::from_raw(group_id))
-            .map_err(|err| err.into_pyexception(vm))?;
+            let metadata = metadata.map_err(|err| err.into_pyexception(vm))?;
 
-        let r_ok = !flags.contains(AccessFlags::R_OK) || perm.is_readable;
-        let w_ok = !flags.contains(AccessFlags::W_OK) || perm.is_writable;
-        let x_ok = !flags.contains(AccessFlags::X_OK) || perm.is_executable;
+            let user_id = metadata.uid();
+            let group_id = metadata.gid();
+            let mode = metadata.mode();
 
-        Ok(r_ok && w_ok && x_ok)
+            let perm = get_right_permission(mode, Uid::from_raw(user_id), Gid::from_raw(group_id))
+                .map_err(|err| err.into_pyexception(vm))?;
+
+            let r_ok = !flags.contains(AccessFlags::R_OK) || perm.is_readable;
+            let w_ok = !flags.contains(AccessFlags::W_OK) || perm.is_writable;
+            let x_ok = !flags.contains(AccessFlags::X_OK) || perm.is_executable;
+
+            Ok(r_ok && w_ok && x_ok)
+        }
     }
 
     pub(super) fn bytes_as_osstr<'a>(
@@ -1664,20 +2556,115 @@ mod posix {
     }
 
     #[pyattr]
-    fn environ(vm: &VirtualMachine) -> PyDictRef {
-        let environ = vm.ctx.new_dict();
-        use std::os::unix::ffi::OsStringExt;
-        for (key, value) in env::vars_os() {
-            environ
-                .set_item(
-                    vm.ctx.new_bytes(key.into_vec()),
-                    vm.ctx.new_bytes(value.into_vec()),
-                    vm,
-                )
-                .unwrap();
+    #[pyclass(name = "Environ")]
+    #[derive(Debug)]
+    struct PyEnviron {}
+
+    impl PyValue for PyEnviron {
+        fn class(_vm: &VirtualMachine) -> &PyTypeRef {
+            Self::static_type()
         }
+    }
 
-        environ
+    type EnvKey = Either<PyStrRef, PyBytesRef>;
+
+    fn env_key_as_osstr<'a>(key: &'a EnvKey, vm: &VirtualMachine) -> PyResult<&'a ffi::OsStr> {
+        Ok(match key {
+            Either::A(s) => s.borrow_value().as_ref(),
+            Either::B(b) => bytes_as_osstr(b.borrow_value(), vm)?,
+        })
+    }
+
+    // A live, bytes-keyed view of the process environment: every operation
+    // goes through getenv(3)/setenv(3) rather than a snapshot taken at import
+    // time, so putenv() and unsetenv() are reflected immediately.
+    #[pyimpl(with(Iterable))]
+    impl PyEnviron {
+        #[pymethod(magic)]
+        fn getitem(&self, key: EnvKey, vm: &VirtualMachine) -> PyResult {
+            use std::os::unix::ffi::OsStringExt;
+            let value = env::var_os(env_key_as_osstr(&key, vm)?);
+            match value {
+                Some(value) => Ok(vm.ctx.new_bytes(value.into_vec())),
+                None => Err(vm.new_key_error(key.into_pyobject(vm))),
+            }
+        }
+
+        #[pymethod(magic)]
+        fn setitem(&self, key: EnvKey, value: EnvKey, vm: &VirtualMachine) -> PyResult<()> {
+            env::set_var(env_key_as_osstr(&key, vm)?, env_key_as_osstr(&value, vm)?);
+            Ok(())
+        }
+
+        // deliberately no KeyError on a missing key: Lib/os.py calls
+        // unsetenv() before deleting from its backing mapping, so by the time
+        // the deletion reaches a live view the key is already gone
+        #[pymethod(magic)]
+        fn delitem(&self, key: EnvKey, vm: &VirtualMachine) -> PyResult<()> {
+            env::remove_var(env_key_as_osstr(&key, vm)?);
+            Ok(())
+        }
+
+        #[pymethod(magic)]
+        fn contains(&self, key: EnvKey, vm: &VirtualMachine) -> PyResult<bool> {
+            Ok(env::var_os(env_key_as_osstr(&key, vm)?).is_some())
+        }
+
+        #[pymethod(magic)]
+        fn len(&self) -> usize {
+            env::vars_os().count()
+        }
+
+        #[pymethod]
+        fn items(&self, vm: &VirtualMachine) -> PyObjectRef {
+            use std::os::unix::ffi::OsStringExt;
+            let items = env::vars_os()
+                .map(|(key, value)| {
+                    vm.ctx.new_tuple(vec![
+                        vm.ctx.new_bytes(key.into_vec()),
+                        vm.ctx.new_bytes(value.into_vec()),
+                    ])
+                })
+                .collect();
+            vm.ctx.new_list(items)
+        }
+    }
+
+    impl Iterable for PyEnviron {
+        fn iter(_zelf: PyRef<Self>, vm: &VirtualMachine) -> PyResult {
+            use std::os::unix::ffi::OsStringExt;
+            let keys = env::vars_os()
+                .map(|(key, _)| vm.ctx.new_bytes(key.into_vec()))
+                .collect();
+            crate::iterator::get_iter(vm, vm.ctx.new_list(keys))
+        }
+    }
+
+    #[pyattr]
+    fn environ(vm: &VirtualMachine) -> PyObjectRef {
+        PyEnviron {}.into_ref(vm).into_object()
+    }
+
+    // the environment is bytes-keyed to begin with on unix, so environb is
+    // just another handle on the same live view; windows deliberately has no
+    // equivalent attribute
+    #[pyattr]
+    fn environb(vm: &VirtualMachine) -> PyObjectRef {
+        PyEnviron {}.into_ref(vm).into_object()
+    }
+
+    #[pyfunction]
+    fn getenvb(
+        key: PyBytesRef,
+        default: OptionalArg<PyObjectRef>,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        use std::os::unix::ffi::OsStringExt;
+        let value = env::var_os(bytes_as_osstr(key.borrow_value(), vm)?);
+        Ok(match value {
+            Some(value) => vm.ctx.new_bytes(value.into_vec()),
+            None => default.unwrap_or_none(vm),
+        })
     }
 
     #[pyfunction]
@@ -1737,12 +2724,7 @@ mod posix {
 
         match path {
             Either::A(p) => nix::unistd::fchownat(dir_fd.0, p.path.as_os_str(), uid, gid, flag),
-            Either::B(fd) => {
-                let path = fs::read_link(format!("/proc/self/fd/{}", fd)).map_err(|_| {
-                    vm.new_os_error(String::from("Cannot find path for specified fd"))
-                })?;
-                nix::unistd::fchownat(dir_fd.0, &path, uid, gid, flag)
-            }
+            Either::B(fd) => nix::unistd::fchown(fd as RawFd, uid, gid),
         }
         .map_err(|err| err.into_pyexception(vm))
     }
@@ -1881,6 +2863,30 @@ mod posix {
         vm: &VirtualMachine,
     ) -> PyResult<()> {
         let path = make_path(vm, &path, &dir_fd)?;
+        if !follow_symlinks.0 {
+            use std::os::unix::ffi::OsStrExt;
+            let path = ffi::CString::new(path.as_bytes())
+                .map_err(|_| vm.new_value_error("embedded null character".to_owned()))?;
+            let ret = unsafe {
+                libc::fchmodat(
+                    libc::AT_FDCWD,
+                    path.as_ptr(),
+                    mode as libc::mode_t,
+                    libc::AT_SYMLINK_NOFOLLOW,
+                )
+            };
+            return if ret == 0 {
+                Ok(())
+            } else if Errno::last() as i32 == libc::ENOTSUP {
+                // Linux can't change the mode of a symlink itself; CPython
+                // turns the kernel's ENOTSUP into NotImplementedError
+                Err(vm.new_not_implemented_error(
+                    "chmod: follow_symlinks unavailable on this platform".to_owned(),
+                ))
+            } else {
+                Err(errno_err(vm))
+            };
+        }
         let body = move || {
             use std::os::unix::fs::PermissionsExt;
             let meta = fs_metadata(&path, follow_symlinks.0)?;
@@ -2075,36 +3081,1100 @@ mod posix {
         .map_err(|err| err.into_pyexception(vm))
     }
 
-    #[cfg(not(target_os = "redox"))]
     #[pyfunction]
-    fn openpty(vm: &VirtualMachine) -> PyResult {
-        let r = nix::pty::openpty(None, None).map_err(|err| err.into_pyexception(vm))?;
-        Ok(vm
-            .ctx
-            .new_tuple(vec![vm.ctx.new_int(r.master), vm.ctx.new_int(r.slave)]))
+    fn fork(vm: &VirtualMachine) -> PyResult<libc::pid_t> {
+        run_at_forkers(AtForkStage::Before, vm);
+        // Safety: the caller is responsible for any fork-unsafe state (threads,
+        // lock ordering); this matches CPython, which forks regardless.
+        let res = unsafe { nix::unistd::fork() }.map_err(|err| err.into_pyexception(vm))?;
+        Ok(match res {
+            nix::unistd::ForkResult::Parent { child } => {
+                run_at_forkers(AtForkStage::AfterInParent, vm);
+                child.as_raw()
+            }
+            nix::unistd::ForkResult::Child => {
+                run_at_forkers(AtForkStage::AfterInChild, vm);
+                0
+            }
+        })
     }
 
+    #[cfg(not(target_os = "redox"))]
     #[pyfunction]
-    fn ttyname(fd: i32, vm: &VirtualMachine) -> PyResult {
-        let name = unsafe { libc::ttyname(fd) };
-        if name.is_null() {
-            Err(errno_err(vm))
-        } else {
-            let name = unsafe { ffi::CStr::from_ptr(name) }.to_str().unwrap();
-            Ok(vm.ctx.new_str(name))
-        }
+    fn forkpty(vm: &VirtualMachine) -> PyResult<(libc::pid_t, i32)> {
+        run_at_forkers(AtForkStage::Before, vm);
+        let r = nix::pty::forkpty(None, None).map_err(|err| err.into_pyexception(vm))?;
+        Ok(match r.fork_result {
+            nix::unistd::ForkResult::Parent { child } => {
+                run_at_forkers(AtForkStage::AfterInParent, vm);
+                (child.as_raw(), r.master)
+            }
+            // the child's controlling terminal is the slave; it has no use for
+            // the master fd
+            nix::unistd::ForkResult::Child => {
+                run_at_forkers(AtForkStage::AfterInChild, vm);
+                (0, -1)
+            }
+        })
     }
 
-    #[pyfunction]
-    fn umask(mask: libc::mode_t) -> libc::mode_t {
-        unsafe { libc::umask(mask) }
+    enum AtForkStage {
+        Before,
+        AfterInChild,
+        AfterInParent,
     }
 
-    #[pyattr]
-    #[pyclass(module = "os", name = "uname_result")]
-    #[derive(Debug, PyStructSequence)]
-    struct UnameResult {
-        sysname: String,
+    /// Hook failures must not abort the fork itself, so they are reported the
+    /// same way CPython reports unraisable exceptions and then ignored.
+    fn run_at_forkers(stage: AtForkStage, vm: &VirtualMachine) {
+        let hooks = vm.state.atfork_hooks.lock();
+        let funcs = match stage {
+            AtForkStage::Before => &hooks.before,
+            AtForkStage::AfterInChild => &hooks.after_in_child,
+            AtForkStage::AfterInParent => &hooks.after_in_parent,
+        }
+        .clone();
+        drop(hooks);
+        // before-hooks run in reverse registration order, after-hooks forwards
+        let funcs: Box<dyn Iterator<Item = _>> = match stage {
+            AtForkStage::Before => Box::new(funcs.into_iter().rev()),
+            _ => Box::new(funcs.into_iter()),
+        };
+        for func in funcs {
+            if let Err(e) = vm.invoke(&func, ()) {
+                writeln!(
+                    crate::sysmodule::PyStderr(vm),
+                    "Exception ignored in fork hook:"
+                );
+                crate::exceptions::print_exception(vm, e);
+            }
+        }
+    }
+
+    #[derive(FromArgs)]
+    struct RegisterAtForkArgs {
+        #[pyarg(named, default)]
+        before: Option<PyObjectRef>,
+        #[pyarg(named, default)]
+        after_in_child: Option<PyObjectRef>,
+        #[pyarg(named, default)]
+        after_in_parent: Option<PyObjectRef>,
+    }
+
+    #[pyfunction]
+    fn register_at_fork(args: RegisterAtForkArgs, vm: &VirtualMachine) -> PyResult<()> {
+        fn push_hook(
+            hooks: &mut Vec<PyObjectRef>,
+            func: Option<PyObjectRef>,
+            vm: &VirtualMachine,
+        ) -> PyResult<()> {
+            if let Some(func) = func {
+                if !vm.is_callable(&func) {
+                    return Err(vm.new_type_error("Args must be callable".to_owned()));
+                }
+                hooks.push(func);
+            }
+            Ok(())
+        }
+        if args.before.is_none() && args.after_in_child.is_none() && args.after_in_parent.is_none()
+        {
+            return Err(vm.new_type_error("At least one argument is required".to_owned()));
+        }
+        let mut hooks = vm.state.atfork_hooks.lock();
+        push_hook(&mut hooks.before, args.before, vm)?;
+        push_hook(&mut hooks.after_in_child, args.after_in_child, vm)?;
+        push_hook(&mut hooks.after_in_parent, args.after_in_parent, vm)?;
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn openpty(vm: &VirtualMachine) -> PyResult {
+        let r = nix::pty::openpty(None, None).map_err(|err| err.into_pyexception(vm))?;
+        Ok(vm
+            .ctx
+            .new_tuple(vec![vm.ctx.new_int(r.master), vm.ctx.new_int(r.slave)]))
+    }
+
+    #[pyfunction]
+    fn ttyname(fd: i32, vm: &VirtualMachine) -> PyResult {
+        let name = unsafe { libc::ttyname(fd) };
+        if name.is_null() {
+            Err(errno_err(vm))
+        } else {
+            let name = unsafe { ffi::CStr::from_ptr(name) }.to_str().unwrap();
+            Ok(vm.ctx.new_str(name))
+        }
+    }
+
+    #[pyfunction]
+    fn umask(mask: libc::mode_t) -> libc::mode_t {
+        unsafe { libc::umask(mask) }
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn mkfifo(
+        path: PyPathLike,
+        mode: OptionalArg<i32>,
+        dir_fd: DirFd,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        let mode = mode.unwrap_or(0o666);
+        let path = ffi::CString::new(path.into_bytes())
+            .map_err(|_| vm.new_value_error("embedded null character".to_owned()))?;
+        #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+        if let Some(fd) = dir_fd.0 {
+            let ret = unsafe { libc::mkfifoat(fd, path.as_ptr(), mode as libc::mode_t) };
+            return Errno::result(ret)
+                .map(drop)
+                .map_err(|err| err.into_pyexception(vm));
+        }
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        if dir_fd.0.is_some() {
+            return Err(vm.new_not_implemented_error(
+                "mkfifo: dir_fd unavailable on this platform".to_owned(),
+            ));
+        }
+        let ret = unsafe { libc::mkfifo(path.as_ptr(), mode as libc::mode_t) };
+        Errno::result(ret)
+            .map(drop)
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn mknod(
+        path: PyPathLike,
+        mode: OptionalArg<i32>,
+        device: OptionalArg<libc::dev_t>,
+        dir_fd: DirFd,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        let mode = mode.unwrap_or(0o600);
+        let device = device.unwrap_or(0);
+        let path = ffi::CString::new(path.into_bytes())
+            .map_err(|_| vm.new_value_error("embedded null character".to_owned()))?;
+        // creating S_IFBLK/S_IFCHR nodes needs privilege; the kernel's EPERM
+        // surfaces as PermissionError, so no extra validation is required here
+        #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+        if let Some(fd) = dir_fd.0 {
+            let ret = unsafe { libc::mknodat(fd, path.as_ptr(), mode as libc::mode_t, device) };
+            return Errno::result(ret)
+                .map(drop)
+                .map_err(|err| err.into_pyexception(vm));
+        }
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        if dir_fd.0.is_some() {
+            return Err(vm.new_not_implemented_error(
+                "mknod: dir_fd unavailable on this platform".to_owned(),
+            ));
+        }
+        let ret = unsafe { libc::mknod(path.as_ptr(), mode as libc::mode_t, device) };
+        Errno::result(ret)
+            .map(drop)
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "emscripten"))]
+    #[pyfunction]
+    fn makedev(major: libc::c_uint, minor: libc::c_uint) -> libc::dev_t {
+        unsafe { libc::makedev(major, minor) }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "emscripten"))]
+    #[pyfunction]
+    fn major(device: libc::dev_t) -> libc::c_uint {
+        unsafe { libc::major(device) }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "emscripten"))]
+    #[pyfunction]
+    fn minor(device: libc::dev_t) -> libc::c_uint {
+        unsafe { libc::minor(device) }
+    }
+
+    // <sys/types.h>: an 8-bit major and a 24-bit minor
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    #[pyfunction]
+    fn makedev(major: i32, minor: i32) -> libc::dev_t {
+        ((major << 24) | (minor & 0x00ff_ffff)) as libc::dev_t
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    #[pyfunction]
+    fn major(device: libc::dev_t) -> i32 {
+        (device >> 24) & 0xff
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    #[pyfunction]
+    fn minor(device: libc::dev_t) -> i32 {
+        device & 0x00ff_ffff
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn writev(fd: i32, buffers: PyObjectRef, vm: &VirtualMachine) -> PyResult<isize> {
+        let buffers = vm.extract_elements::<PyBytesLike>(&buffers)?;
+        let borrowed: Vec<_> = buffers.iter().map(|b| b.borrow_value()).collect();
+        let iov: Vec<libc::iovec> = borrowed
+            .iter()
+            .map(|data| libc::iovec {
+                iov_base: data.as_ptr() as *mut libc::c_void,
+                iov_len: data.len(),
+            })
+            .collect();
+        let ret = loop {
+            let ret = unsafe { libc::writev(fd, iov.as_ptr(), iov.len() as i32) };
+            if ret != -1 || Errno::last() != Errno::EINTR {
+                break ret;
+            }
+        };
+        Errno::result(ret).map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn readv(fd: i32, buffers: PyObjectRef, vm: &VirtualMachine) -> PyResult<isize> {
+        let buffers = vm.extract_elements::<PyRwBytesLike>(&buffers)?;
+        let mut borrowed: Vec<_> = buffers.iter().map(|b| b.borrow_value()).collect();
+        let iov: Vec<libc::iovec> = borrowed
+            .iter_mut()
+            .map(|data| libc::iovec {
+                iov_base: data.as_mut_ptr() as *mut libc::c_void,
+                iov_len: data.len(),
+            })
+            .collect();
+        let ret = loop {
+            let ret = unsafe { libc::readv(fd, iov.as_ptr(), iov.len() as i32) };
+            if ret != -1 || Errno::last() != Errno::EINTR {
+                break ret;
+            }
+        };
+        Errno::result(ret).map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    #[pyattr]
+    use libc::{RWF_DSYNC, RWF_HIPRI, RWF_NOWAIT, RWF_SYNC};
+
+    #[cfg(target_os = "linux")]
+    #[pyfunction]
+    fn copy_file_range(
+        src: i32,
+        dst: i32,
+        count: i64,
+        offset_src: OptionalArg<Option<Offset>>,
+        offset_dst: OptionalArg<Option<Offset>>,
+        vm: &VirtualMachine,
+    ) -> PyResult<usize> {
+        if count < 0 {
+            return Err(vm.new_value_error("negative value for 'count' not allowed".to_owned()));
+        }
+        // a null offset pointer means "use (and update) the fd's own position"
+        let mut offset_src = offset_src.into_option().flatten();
+        let mut offset_dst = offset_dst.into_option().flatten();
+        let p_offset_src = offset_src
+            .as_mut()
+            .map_or(std::ptr::null_mut(), |x| x as *mut Offset);
+        let p_offset_dst = offset_dst
+            .as_mut()
+            .map_or(std::ptr::null_mut(), |x| x as *mut Offset);
+        // go through syscall() so this works on non-glibc targets too; an
+        // ENOSYS error from older kernels is surfaced as a plain OSError
+        let ret = unsafe {
+            libc::syscall(
+                libc::SYS_copy_file_range,
+                src,
+                p_offset_src,
+                dst,
+                p_offset_dst,
+                count as usize,
+                0u32,
+            )
+        };
+        Errno::result(ret)
+            .map(|ret| ret as usize)
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(target_os = "linux")]
+    #[pyattr]
+    use libc::{SPLICE_F_GIFT, SPLICE_F_MORE, SPLICE_F_MOVE, SPLICE_F_NONBLOCK};
+
+    #[cfg(target_os = "linux")]
+    #[pyattr]
+    use libc::{
+        FALLOC_FL_COLLAPSE_RANGE, FALLOC_FL_INSERT_RANGE, FALLOC_FL_KEEP_SIZE,
+        FALLOC_FL_PUNCH_HOLE, FALLOC_FL_ZERO_RANGE,
+    };
+
+    #[cfg(target_os = "linux")]
+    #[pyfunction]
+    fn fallocate(fd: i32, mode: i32, offset: Offset, length: Offset, vm: &VirtualMachine) -> PyResult<()> {
+        let ret = unsafe { libc::fallocate(fd, mode, offset, length) };
+        Errno::result(ret)
+            .map(drop)
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(target_os = "linux")]
+    #[pyattr]
+    use libc::{GRND_NONBLOCK, GRND_RANDOM};
+
+    #[cfg(target_os = "linux")]
+    #[pyfunction]
+    fn getrandom(size: isize, flags: OptionalArg<u32>, vm: &VirtualMachine) -> PyResult<Vec<u8>> {
+        if size < 0 {
+            return Err(vm.new_value_error("negative argument not allowed".to_owned()));
+        }
+        let mut buf = vec![0u8; size as usize];
+        // unlike urandom, this is a single shot at the syscall: with
+        // GRND_NONBLOCK it may fail, and it may fill less than requested
+        let ret = unsafe {
+            libc::getrandom(
+                buf.as_mut_ptr() as *mut libc::c_void,
+                buf.len(),
+                flags.unwrap_or(0),
+            )
+        };
+        let filled = Errno::result(ret).map_err(|err| err.into_pyexception(vm))?;
+        buf.truncate(filled as usize);
+        Ok(buf)
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "emscripten",
+        target_os = "freebsd"
+    ))]
+    #[pyattr]
+    use libc::{
+        POSIX_FADV_DONTNEED, POSIX_FADV_NOREUSE, POSIX_FADV_NORMAL, POSIX_FADV_RANDOM,
+        POSIX_FADV_SEQUENTIAL, POSIX_FADV_WILLNEED,
+    };
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "emscripten",
+        target_os = "freebsd"
+    ))]
+    #[pyfunction]
+    fn posix_fadvise(
+        fd: i32,
+        offset: Offset,
+        length: Offset,
+        advice: i32,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        // like posix_fallocate, the error comes back as the return value
+        let ret = unsafe { libc::posix_fadvise(fd, offset, length, advice) };
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(nix::Error::Sys(Errno::from_i32(ret)).into_pyexception(vm))
+        }
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "emscripten",
+        target_os = "freebsd"
+    ))]
+    #[pyfunction]
+    fn posix_fallocate(fd: i32, offset: Offset, length: Offset, vm: &VirtualMachine) -> PyResult<()> {
+        let ret = unsafe { libc::posix_fallocate(fd, offset, length) };
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(nix::Error::Sys(Errno::from_i32(ret)).into_pyexception(vm))
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[pyattr]
+    use libc::{MFD_ALLOW_SEALING, MFD_CLOEXEC, MFD_HUGETLB};
+    // from <linux/memfd.h>; not exposed by the libc crate
+    #[cfg(target_os = "linux")]
+    #[pyattr]
+    const MFD_HUGE_SHIFT: u32 = 26;
+    #[cfg(target_os = "linux")]
+    #[pyattr]
+    const MFD_HUGE_MASK: u32 = 0x3f;
+    #[cfg(target_os = "linux")]
+    #[pyattr]
+    const MFD_HUGE_2MB: u32 = 21 << MFD_HUGE_SHIFT;
+    #[cfg(target_os = "linux")]
+    #[pyattr]
+    const MFD_HUGE_1GB: u32 = 30 << MFD_HUGE_SHIFT;
+
+    #[cfg(target_os = "linux")]
+    #[pyfunction]
+    fn memfd_create(name: PyStrRef, flags: OptionalArg<u32>, vm: &VirtualMachine) -> PyResult<i32> {
+        let name = ffi::CString::new(name.borrow_value())
+            .map_err(|_| vm.new_value_error("embedded null character".to_owned()))?;
+        // go through syscall(): glibc only grew the wrapper in 2.27
+        let ret =
+            unsafe { libc::syscall(libc::SYS_memfd_create, name.as_ptr(), flags.unwrap_or(0)) };
+        Errno::result(ret)
+            .map(|fd| fd as i32)
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(target_os = "linux")]
+    #[pyattr]
+    use libc::{EFD_CLOEXEC, EFD_NONBLOCK, EFD_SEMAPHORE};
+
+    #[cfg(target_os = "linux")]
+    #[pyfunction]
+    fn eventfd(initval: u32, flags: OptionalArg<i32>, vm: &VirtualMachine) -> PyResult<i32> {
+        let ret = unsafe { libc::eventfd(initval, flags.unwrap_or(0)) };
+        Errno::result(ret).map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(target_os = "linux")]
+    #[pyfunction]
+    fn eventfd_read(fd: i32, vm: &VirtualMachine) -> PyResult<u64> {
+        let mut buf = [0u8; 8];
+        let ret = unsafe { libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+        Errno::result(ret).map_err(|err| err.into_pyexception(vm))?;
+        Ok(u64::from_ne_bytes(buf))
+    }
+
+    #[cfg(target_os = "linux")]
+    #[pyfunction]
+    fn eventfd_write(fd: i32, value: u64, vm: &VirtualMachine) -> PyResult<()> {
+        let buf = value.to_ne_bytes();
+        let ret = unsafe { libc::write(fd, buf.as_ptr() as *const libc::c_void, buf.len()) };
+        Errno::result(ret)
+            .map(drop)
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(target_os = "linux")]
+    #[pyattr]
+    use libc::{XATTR_CREATE, XATTR_REPLACE};
+
+    /// Size-probe/fetch loop shared by getxattr and listxattr; retries when the
+    /// value grows between the two calls (ERANGE).
+    #[cfg(target_os = "linux")]
+    fn xattr_get_loop(
+        mut call: impl FnMut(*mut libc::c_void, usize) -> isize,
+    ) -> nix::Result<Vec<u8>> {
+        loop {
+            let size = Errno::result(call(std::ptr::null_mut(), 0))? as usize;
+            let mut buf = vec![0u8; size];
+            match Errno::result(call(buf.as_mut_ptr() as *mut libc::c_void, size)) {
+                Ok(len) => {
+                    buf.truncate(len as usize);
+                    return Ok(buf);
+                }
+                Err(nix::Error::Sys(Errno::ERANGE)) => continue,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn xattr_cstring(path: PyPathLike, vm: &VirtualMachine) -> PyResult<ffi::CString> {
+        ffi::CString::new(path.into_bytes())
+            .map_err(|_| vm.new_value_error("embedded null character".to_owned()))
+    }
+
+    #[cfg(target_os = "linux")]
+    #[pyfunction]
+    fn getxattr(
+        path: Either<PyPathLike, i32>,
+        attribute: PyPathLike,
+        follow_symlinks: FollowSymlinks,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let attr = xattr_cstring(attribute, vm)?;
+        let res = match path {
+            Either::A(p) => {
+                let p = xattr_cstring(p, vm)?;
+                xattr_get_loop(|buf, size| unsafe {
+                    if follow_symlinks.0 {
+                        libc::getxattr(p.as_ptr(), attr.as_ptr(), buf, size)
+                    } else {
+                        libc::lgetxattr(p.as_ptr(), attr.as_ptr(), buf, size)
+                    }
+                })
+            }
+            Either::B(fd) => xattr_get_loop(|buf, size| unsafe {
+                libc::fgetxattr(fd, attr.as_ptr(), buf, size)
+            }),
+        }
+        .map_err(|err| err.into_pyexception(vm))?;
+        Ok(vm.ctx.new_bytes(res))
+    }
+
+    #[cfg(target_os = "linux")]
+    #[pyfunction]
+    fn setxattr(
+        path: Either<PyPathLike, i32>,
+        attribute: PyPathLike,
+        value: PyBytesLike,
+        flags: OptionalArg<i32>,
+        follow_symlinks: FollowSymlinks,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        let attr = xattr_cstring(attribute, vm)?;
+        let flags = flags.unwrap_or(0);
+        let ret = value.with_ref(|value| match path {
+            Either::A(p) => {
+                let p = xattr_cstring(p, vm)?;
+                Ok(unsafe {
+                    if follow_symlinks.0 {
+                        libc::setxattr(
+                            p.as_ptr(),
+                            attr.as_ptr(),
+                            value.as_ptr() as *const libc::c_void,
+                            value.len(),
+                            flags,
+                        )
+                    } else {
+                        libc::lsetxattr(
+                            p.as_ptr(),
+                            attr.as_ptr(),
+                            value.as_ptr() as *const libc::c_void,
+                            value.len(),
+                            flags,
+                        )
+                    }
+                })
+            }
+            Either::B(fd) => Ok(unsafe {
+                libc::fsetxattr(
+                    fd,
+                    attr.as_ptr(),
+                    value.as_ptr() as *const libc::c_void,
+                    value.len(),
+                    flags,
+                )
+            }),
+        })?;
+        Errno::result(ret)
+            .map(drop)
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(target_os = "linux")]
+    #[pyfunction]
+    fn listxattr(
+        path: OptionalArg<Either<PyPathLike, i32>>,
+        follow_symlinks: FollowSymlinks,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let path = path.unwrap_or_else(|| Either::A(PyPathLike::new_str(".")));
+        let buf = match path {
+            Either::A(p) => {
+                let p = xattr_cstring(p, vm)?;
+                xattr_get_loop(|buf, size| unsafe {
+                    if follow_symlinks.0 {
+                        libc::listxattr(p.as_ptr(), buf as *mut libc::c_char, size)
+                    } else {
+                        libc::llistxattr(p.as_ptr(), buf as *mut libc::c_char, size)
+                    }
+                })
+            }
+            Either::B(fd) => xattr_get_loop(|buf, size| unsafe {
+                libc::flistxattr(fd, buf as *mut libc::c_char, size)
+            }),
+        }
+        .map_err(|err| err.into_pyexception(vm))?;
+        let names = buf
+            .split(|&b| b == 0)
+            .filter(|name| !name.is_empty())
+            .map(|name| vm.ctx.new_str(String::from_utf8_lossy(name).into_owned()))
+            .collect();
+        Ok(vm.ctx.new_list(names))
+    }
+
+    #[cfg(target_os = "linux")]
+    #[pyfunction]
+    fn removexattr(
+        path: Either<PyPathLike, i32>,
+        attribute: PyPathLike,
+        follow_symlinks: FollowSymlinks,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        let attr = xattr_cstring(attribute, vm)?;
+        let ret = match path {
+            Either::A(p) => {
+                let p = xattr_cstring(p, vm)?;
+                unsafe {
+                    if follow_symlinks.0 {
+                        libc::removexattr(p.as_ptr(), attr.as_ptr())
+                    } else {
+                        libc::lremovexattr(p.as_ptr(), attr.as_ptr())
+                    }
+                }
+            }
+            Either::B(fd) => unsafe { libc::fremovexattr(fd, attr.as_ptr()) },
+        };
+        Errno::result(ret)
+            .map(drop)
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[pyfunction]
+    fn fchmod(fd: i32, mode: libc::mode_t, vm: &VirtualMachine) -> PyResult<()> {
+        let ret = unsafe { libc::fchmod(fd, mode) };
+        Errno::result(ret)
+            .map(drop)
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn lchmod(path: PyPathLike, mode: libc::mode_t, vm: &VirtualMachine) -> PyResult<()> {
+        let path = ffi::CString::new(path.into_bytes())
+            .map_err(|_| vm.new_value_error("embedded null character".to_owned()))?;
+        // libc has no lchmod binding; fchmodat with AT_SYMLINK_NOFOLLOW is its
+        // modern spelling (natively supported on the BSDs, emulated by glibc)
+        let ret = unsafe {
+            libc::fchmodat(
+                libc::AT_FDCWD,
+                path.as_ptr(),
+                mode,
+                libc::AT_SYMLINK_NOFOLLOW,
+            )
+        };
+        Errno::result(ret)
+            .map(drop)
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    #[pyattr]
+    use libc::{SF_APPEND, SF_IMMUTABLE, UF_APPEND, UF_IMMUTABLE, UF_NODUMP, UF_OPAQUE};
+    #[cfg(target_os = "macos")]
+    #[pyattr]
+    use libc::UF_COMPRESSED;
+    #[cfg(target_os = "freebsd")]
+    #[pyattr]
+    use libc::SF_NOUNLINK;
+
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    #[pyfunction]
+    fn chflags(path: PyPathLike, flags: u32, vm: &VirtualMachine) -> PyResult<()> {
+        let path = ffi::CString::new(path.into_bytes())
+            .map_err(|_| vm.new_value_error("embedded null character".to_owned()))?;
+        let ret = unsafe { libc::chflags(path.as_ptr(), flags as _) };
+        Errno::result(ret)
+            .map(drop)
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    #[pyfunction]
+    fn lchflags(path: PyPathLike, flags: u32, vm: &VirtualMachine) -> PyResult<()> {
+        let path = ffi::CString::new(path.into_bytes())
+            .map_err(|_| vm.new_value_error("embedded null character".to_owned()))?;
+        #[cfg(any(target_os = "freebsd", target_os = "netbsd"))]
+        let ret = unsafe { libc::lchflags(path.as_ptr(), flags as _) };
+        // libc doesn't bind lchflags(2) for these even though the OS has it
+        #[cfg(any(target_os = "macos", target_os = "openbsd"))]
+        let ret = {
+            extern "C" {
+                fn lchflags(path: *const libc::c_char, flags: libc::c_uint) -> libc::c_int;
+            }
+            unsafe { lchflags(path.as_ptr(), flags as _) }
+        };
+        Errno::result(ret)
+            .map(drop)
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn ctermid(vm: &VirtualMachine) -> PyResult<String> {
+        // libc doesn't bind ctermid(3); use our own buffer instead of the
+        // non-thread-safe static one
+        extern "C" {
+            fn ctermid(s: *mut libc::c_char) -> *mut libc::c_char;
+        }
+        let mut buf = [0 as libc::c_char; 128]; // >= L_ctermid everywhere
+        let ptr = unsafe { ctermid(buf.as_mut_ptr()) };
+        if ptr.is_null() {
+            return Err(errno_err(vm));
+        }
+        Ok(unsafe { ffi::CStr::from_ptr(ptr) }
+            .to_string_lossy()
+            .into_owned())
+    }
+
+    #[pyfunction]
+    fn tcgetpgrp(fd: i32, vm: &VirtualMachine) -> PyResult<libc::pid_t> {
+        unistd::tcgetpgrp(fd)
+            .map(|pgid| pgid.as_raw())
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[pyfunction]
+    fn tcsetpgrp(fd: i32, pgid: libc::pid_t, vm: &VirtualMachine) -> PyResult<()> {
+        unistd::tcsetpgrp(fd, Pid::from_raw(pgid)).map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn fchdir(fd: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+        // accept a raw fd or anything with a fileno(), like CPython's
+        // fildes converter
+        let fd = match i32::try_from_object(vm, fd.clone()) {
+            Ok(fd) => fd,
+            Err(_) => i32::try_from_object(vm, vm.call_method(&fd, "fileno", ())?)?,
+        };
+        nix::unistd::fchdir(fd).map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[pyfunction]
+    fn times(vm: &VirtualMachine) -> PyResult {
+        let mut t: libc::tms = unsafe { std::mem::zeroed() };
+        let tick_for_second = unsafe { libc::sysconf(libc::_SC_CLK_TCK) } as f64;
+        let c = unsafe { libc::times(&mut t) };
+        if c == -1 as libc::clock_t {
+            return Err(errno_err(vm));
+        }
+        super::_os::TimesResult {
+            user: t.tms_utime as f64 / tick_for_second,
+            system: t.tms_stime as f64 / tick_for_second,
+            children_user: t.tms_cutime as f64 / tick_for_second,
+            children_system: t.tms_cstime as f64 / tick_for_second,
+            elapsed: c as f64 / tick_for_second,
+        }
+        .into_struct_sequence(vm)
+        .map(|t| t.into_object())
+    }
+
+    #[pyfunction]
+    fn nice(increment: i32, vm: &VirtualMachine) -> PyResult<i32> {
+        if !(-20..=19).contains(&increment) {
+            return Err(vm.new_value_error("nice value out of range (-20 to 19)".to_owned()));
+        }
+        // nice(2) may legitimately return -1, so errno is the only failure signal
+        Errno::clear();
+        let niceness = unsafe { libc::nice(increment) };
+        if niceness == -1 && errno() != 0 {
+            Err(errno_err(vm))
+        } else {
+            Ok(niceness)
+        }
+    }
+
+    #[pyfunction]
+    fn sched_yield(vm: &VirtualMachine) -> PyResult<()> {
+        let ret = unsafe { libc::sched_yield() };
+        Errno::result(ret)
+            .map(drop)
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[pyattr]
+    use libc::{SCHED_BATCH, SCHED_FIFO, SCHED_IDLE, SCHED_OTHER, SCHED_RR};
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[pyfunction]
+    fn sched_get_priority_min(policy: i32, vm: &VirtualMachine) -> PyResult<i32> {
+        let priority = unsafe { libc::sched_get_priority_min(policy) };
+        if priority == -1 {
+            Err(errno_err(vm))
+        } else {
+            Ok(priority)
+        }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[pyfunction]
+    fn sched_get_priority_max(policy: i32, vm: &VirtualMachine) -> PyResult<i32> {
+        let priority = unsafe { libc::sched_get_priority_max(policy) };
+        if priority == -1 {
+            Err(errno_err(vm))
+        } else {
+            Ok(priority)
+        }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[pyattr]
+    #[pyclass(module = "os", name = "sched_param")]
+    #[derive(Debug)]
+    struct SchedParam {
+        sched_priority: PyObjectRef,
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    impl PyValue for SchedParam {
+        fn class(_vm: &VirtualMachine) -> &PyTypeRef {
+            Self::static_type()
+        }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[pyimpl]
+    impl SchedParam {
+        #[pyslot]
+        fn tp_new(
+            cls: PyTypeRef,
+            sched_priority: PyObjectRef,
+            vm: &VirtualMachine,
+        ) -> PyResult<PyRef<Self>> {
+            SchedParam { sched_priority }.into_ref_with_type(vm, cls)
+        }
+
+        #[pyproperty]
+        fn sched_priority(&self) -> PyObjectRef {
+            self.sched_priority.clone()
+        }
+
+        #[pymethod(magic)]
+        fn repr(&self, vm: &VirtualMachine) -> PyResult<String> {
+            Ok(format!(
+                "posix.sched_param(sched_priority = {})",
+                vm.to_repr(&self.sched_priority)?
+            ))
+        }
+
+        fn try_to_libc(&self, vm: &VirtualMachine) -> PyResult<libc::sched_param> {
+            let sched_priority = i32::try_from_object(vm, self.sched_priority.clone())?;
+            Ok(libc::sched_param { sched_priority })
+        }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[pyfunction]
+    fn sched_getscheduler(pid: libc::pid_t, vm: &VirtualMachine) -> PyResult<i32> {
+        let policy = unsafe { libc::sched_getscheduler(pid) };
+        if policy == -1 {
+            Err(errno_err(vm))
+        } else {
+            Ok(policy)
+        }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[pyfunction]
+    fn sched_setscheduler(
+        pid: libc::pid_t,
+        policy: i32,
+        param: PyRef<SchedParam>,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        let param = param.try_to_libc(vm)?;
+        let ret = unsafe { libc::sched_setscheduler(pid, policy, &param) };
+        Errno::result(ret)
+            .map(drop)
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[pyfunction]
+    fn sched_getaffinity(pid: libc::pid_t, vm: &VirtualMachine) -> PyResult<PyRef<PySet>> {
+        let mut cpuset: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+        let ret = unsafe {
+            libc::sched_getaffinity(pid, std::mem::size_of::<libc::cpu_set_t>(), &mut cpuset)
+        };
+        Errno::result(ret).map_err(|err| err.into_pyexception(vm))?;
+        let cpus = PySet::default().into_ref(vm);
+        for cpu in 0..libc::CPU_SETSIZE as usize {
+            if unsafe { libc::CPU_ISSET(cpu, &cpuset) } {
+                cpus.clone().add(vm.ctx.new_int(cpu), vm)?;
+            }
+        }
+        Ok(cpus)
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[pyfunction]
+    fn sched_setaffinity(
+        pid: libc::pid_t,
+        mask: PyIterable<usize>,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        let mut cpuset: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+        for cpu in mask.iter(vm)? {
+            let cpu = cpu?;
+            if cpu >= libc::CPU_SETSIZE as usize {
+                return Err(vm.new_value_error(format!("invalid CPU number: {}", cpu)));
+            }
+            unsafe { libc::CPU_SET(cpu, &mut cpuset) };
+        }
+        let ret = unsafe {
+            libc::sched_setaffinity(pid, std::mem::size_of::<libc::cpu_set_t>(), &cpuset)
+        };
+        Errno::result(ret)
+            .map(drop)
+            .map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(target_os = "freebsd")]
+    #[pyfunction]
+    fn fallocate(fd: i32, mode: i32, offset: Offset, length: Offset, vm: &VirtualMachine) -> PyResult<()> {
+        if mode != 0 {
+            // hole punching and friends are Linux-only
+            return Err(nix::Error::Sys(Errno::EOPNOTSUPP).into_pyexception(vm));
+        }
+        let ret = unsafe { libc::posix_fallocate(fd, offset, length) };
+        if ret == 0 {
+            Ok(())
+        } else {
+            // posix_fallocate returns the error instead of setting errno
+            Err(nix::Error::Sys(Errno::from_i32(ret)).into_pyexception(vm))
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[pyfunction]
+    fn splice(
+        src: i32,
+        dst: i32,
+        count: i64,
+        offset_src: OptionalArg<Option<libc::loff_t>>,
+        offset_dst: OptionalArg<Option<libc::loff_t>>,
+        flags: OptionalArg<u32>,
+        vm: &VirtualMachine,
+    ) -> PyResult<usize> {
+        if count < 0 {
+            return Err(vm.new_value_error("negative value for 'count' not allowed".to_owned()));
+        }
+        let mut offset_src = offset_src.into_option().flatten();
+        let mut offset_dst = offset_dst.into_option().flatten();
+        let p_offset_src = offset_src
+            .as_mut()
+            .map_or(std::ptr::null_mut(), |x| x as *mut libc::loff_t);
+        let p_offset_dst = offset_dst
+            .as_mut()
+            .map_or(std::ptr::null_mut(), |x| x as *mut libc::loff_t);
+        let ret = unsafe {
+            libc::splice(
+                src,
+                p_offset_src,
+                dst,
+                p_offset_dst,
+                count as usize,
+                flags.unwrap_or(0),
+            )
+        };
+        Errno::result(ret).map(|ret| ret as usize).map_err(|err| {
+            // the kernel reports "neither fd is a pipe" (among others) as
+            // EINVAL; that's an argument error at the Python level
+            if err == nix::Error::Sys(Errno::EINVAL) {
+                vm.new_value_error("splice() requires a pipe fd".to_owned())
+            } else {
+                err.into_pyexception(vm)
+            }
+        })
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn pwritev(
+        fd: i32,
+        buffers: PyObjectRef,
+        offset: Offset,
+        flags: OptionalArg<i32>,
+        vm: &VirtualMachine,
+    ) -> PyResult<isize> {
+        let flags = flags.unwrap_or(0);
+        let buffers = vm.extract_elements::<PyBytesLike>(&buffers)?;
+        let borrowed: Vec<_> = buffers.iter().map(|b| b.borrow_value()).collect();
+        let iov: Vec<libc::iovec> = borrowed
+            .iter()
+            .map(|data| libc::iovec {
+                iov_base: data.as_ptr() as *mut libc::c_void,
+                iov_len: data.len(),
+            })
+            .collect();
+        let ret = loop {
+            let ret = if flags != 0 {
+                // only pwritev2 understands the RWF_* flags
+                #[cfg(all(target_os = "linux", target_env = "gnu"))]
+                {
+                    unsafe { libc::pwritev2(fd, iov.as_ptr(), iov.len() as i32, offset, flags) }
+                }
+                #[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+                {
+                    return Err(vm.new_not_implemented_error(
+                        "pwritev flags unavailable on this platform".to_owned(),
+                    ));
+                }
+            } else {
+                unsafe { libc::pwritev(fd, iov.as_ptr(), iov.len() as i32, offset) }
+            };
+            if ret != -1 || Errno::last() != Errno::EINTR {
+                break ret;
+            }
+        };
+        Errno::result(ret).map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn preadv(
+        fd: i32,
+        buffers: PyObjectRef,
+        offset: Offset,
+        flags: OptionalArg<i32>,
+        vm: &VirtualMachine,
+    ) -> PyResult<isize> {
+        let flags = flags.unwrap_or(0);
+        let buffers = vm.extract_elements::<PyRwBytesLike>(&buffers)?;
+        let mut borrowed: Vec<_> = buffers.iter().map(|b| b.borrow_value()).collect();
+        let iov: Vec<libc::iovec> = borrowed
+            .iter_mut()
+            .map(|data| libc::iovec {
+                iov_base: data.as_mut_ptr() as *mut libc::c_void,
+                iov_len: data.len(),
+            })
+            .collect();
+        let ret = loop {
+            let ret = if flags != 0 {
+                #[cfg(all(target_os = "linux", target_env = "gnu"))]
+                {
+                    unsafe { libc::preadv2(fd, iov.as_ptr(), iov.len() as i32, offset, flags) }
+                }
+                #[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+                {
+                    return Err(vm.new_not_implemented_error(
+                        "preadv flags unavailable on this platform".to_owned(),
+                    ));
+                }
+            } else {
+                unsafe { libc::preadv(fd, iov.as_ptr(), iov.len() as i32, offset) }
+            };
+            if ret != -1 || Errno::last() != Errno::EINTR {
+                break ret;
+            }
+        };
+        Errno::result(ret).map_err(|err| err.into_pyexception(vm))
+    }
+
+    #[pyattr]
+    #[pyclass(module = "os", name = "uname_result")]
+    #[derive(Debug, PyStructSequence)]
+    struct UnameResult {
+        sysname: String,
         nodename: String,
         release: String,
         version: String,
@@ -2268,6 +4338,14 @@ mod posix {
         file_actions: Option<PyIterable<PyTupleRef>>,
         #[pyarg(named, default)]
         setsigdef: Option<PyIterable<i32>>,
+        #[pyarg(named, default = "false")]
+        resetids: bool,
+        #[pyarg(named, default)]
+        setpgroup: Option<libc::pid_t>,
+        #[pyarg(named, default)]
+        setsigmask: Option<PyIterable<i32>>,
+        #[pyarg(named, default = "false")]
+        setsid: bool,
     }
 
     #[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos"))]
@@ -2281,6 +4359,22 @@ mod posix {
 
     #[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos"))]
     impl PosixSpawnArgs {
+        fn sigset_from_iterable(
+            sigs: PyIterable<i32>,
+            vm: &VirtualMachine,
+        ) -> PyResult<nix::sys::signal::SigSet> {
+            use nix::sys::signal;
+            let mut set = signal::SigSet::empty();
+            for sig in sigs.iter(vm)? {
+                let sig = sig?;
+                let sig = signal::Signal::try_from(sig).map_err(|_| {
+                    vm.new_value_error(format!("signal number {} out of range", sig))
+                })?;
+                set.add(sig);
+            }
+            Ok(set)
+        }
+
         fn spawn(self, spawnp: bool, vm: &VirtualMachine) -> PyResult<libc::pid_t> {
             let path = ffi::CString::new(self.path.into_bytes())
                 .map_err(|_| vm.new_value_error("path should not have nul bytes".to_owned()))?;
@@ -2345,19 +4439,43 @@ mod posix {
                 assert!(libc::posix_spawnattr_init(sa.as_mut_ptr()) == 0);
                 sa.assume_init()
             };
+            let mut flags = 0;
             if let Some(sigs) = self.setsigdef {
-                use nix::sys::signal;
-                let mut set = signal::SigSet::empty();
-                for sig in sigs.iter(vm)? {
-                    let sig = sig?;
-                    let sig = signal::Signal::try_from(sig).map_err(|_| {
-                        vm.new_value_error(format!("signal number {} out of range", sig))
-                    })?;
-                    set.add(sig);
-                }
+                let set = Self::sigset_from_iterable(sigs, vm)?;
                 assert!(
                     unsafe { libc::posix_spawnattr_setsigdefault(&mut attrp, set.as_ref()) } == 0
                 );
+                flags |= libc::POSIX_SPAWN_SETSIGDEF;
+            }
+            if self.resetids {
+                flags |= libc::POSIX_SPAWN_RESETIDS;
+            }
+            if let Some(pgroup) = self.setpgroup {
+                if unsafe { libc::posix_spawnattr_setpgroup(&mut attrp, pgroup) } != 0 {
+                    return Err(errno_err(vm));
+                }
+                flags |= libc::POSIX_SPAWN_SETPGROUP;
+            }
+            if let Some(sigs) = self.setsigmask {
+                let set = Self::sigset_from_iterable(sigs, vm)?;
+                assert!(unsafe { libc::posix_spawnattr_setsigmask(&mut attrp, set.as_ref()) } == 0);
+                flags |= libc::POSIX_SPAWN_SETSIGMASK;
+            }
+            if self.setsid {
+                #[cfg(target_os = "linux")]
+                {
+                    flags |= POSIX_SPAWN_SETSID;
+                }
+                #[cfg(not(target_os = "linux"))]
+                return Err(vm.new_not_implemented_error(
+                    "setsid parameter is not supported on this platform".to_owned(),
+                ));
+            }
+            if flags != 0 {
+                assert!(
+                    unsafe { libc::posix_spawnattr_setflags(&mut attrp, flags as libc::c_short) }
+                        == 0
+                );
             }
 
             let mut args: Vec<ffi::CString> = self
@@ -2447,6 +4565,30 @@ mod posix {
     fn wexitstatus(status: i32) -> i32 {
         libc::WEXITSTATUS(status)
     }
+    #[pyfunction(name = "WIFCONTINUED")]
+    fn wifcontinued(status: i32) -> bool {
+        libc::WIFCONTINUED(status)
+    }
+    #[pyfunction(name = "WCOREDUMP")]
+    fn wcoredump(status: i32) -> bool {
+        libc::WCOREDUMP(status)
+    }
+
+    #[pyfunction]
+    fn waitstatus_to_exitcode(status: i32, vm: &VirtualMachine) -> PyResult<i32> {
+        if libc::WIFEXITED(status) {
+            Ok(libc::WEXITSTATUS(status))
+        } else if libc::WIFSIGNALED(status) {
+            Ok(-libc::WTERMSIG(status))
+        } else if libc::WIFSTOPPED(status) {
+            Err(vm.new_value_error(format!(
+                "process stopped by delivered signal {}",
+                libc::WSTOPSIG(status)
+            )))
+        } else {
+            Err(vm.new_value_error(format!("invalid wait status: {}", status)))
+        }
+    }
 
     #[pyfunction]
     fn waitpid(pid: libc::pid_t, opt: i32, vm: &VirtualMachine) -> PyResult<(libc::pid_t, i32)> {
@@ -2460,6 +4602,375 @@ mod posix {
         waitpid(-1, 0, vm)
     }
 
+    #[cfg(not(target_os = "redox"))]
+    #[pyattr]
+    #[pyclass(module = "os", name = "struct_rusage")]
+    #[derive(Debug, PyStructSequence)]
+    struct StructRusage {
+        ru_utime: f64,
+        ru_stime: f64,
+        ru_maxrss: libc::c_long,
+        ru_ixrss: libc::c_long,
+        ru_idrss: libc::c_long,
+        ru_isrss: libc::c_long,
+        ru_minflt: libc::c_long,
+        ru_majflt: libc::c_long,
+        ru_nswap: libc::c_long,
+        ru_inblock: libc::c_long,
+        ru_oublock: libc::c_long,
+        ru_msgsnd: libc::c_long,
+        ru_msgrcv: libc::c_long,
+        ru_nsignals: libc::c_long,
+        ru_nvcsw: libc::c_long,
+        ru_nivcsw: libc::c_long,
+    }
+    #[cfg(not(target_os = "redox"))]
+    #[pyimpl(with(PyStructSequence))]
+    impl StructRusage {}
+
+    #[cfg(not(target_os = "redox"))]
+    impl From<libc::rusage> for StructRusage {
+        fn from(ru: libc::rusage) -> Self {
+            let tv = |tv: libc::timeval| tv.tv_sec as f64 + tv.tv_usec as f64 * 1e-6;
+            Self {
+                ru_utime: tv(ru.ru_utime),
+                ru_stime: tv(ru.ru_stime),
+                ru_maxrss: ru.ru_maxrss,
+                ru_ixrss: ru.ru_ixrss,
+                ru_idrss: ru.ru_idrss,
+                ru_isrss: ru.ru_isrss,
+                ru_minflt: ru.ru_minflt,
+                ru_majflt: ru.ru_majflt,
+                ru_nswap: ru.ru_nswap,
+                ru_inblock: ru.ru_inblock,
+                ru_oublock: ru.ru_oublock,
+                ru_msgsnd: ru.ru_msgsnd,
+                ru_msgrcv: ru.ru_msgrcv,
+                ru_nsignals: ru.ru_nsignals,
+                ru_nvcsw: ru.ru_nvcsw,
+                ru_nivcsw: ru.ru_nivcsw,
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn wait4(pid: libc::pid_t, options: i32, vm: &VirtualMachine) -> PyResult {
+        let mut status = 0;
+        let mut rusage = unsafe { std::mem::zeroed::<libc::rusage>() };
+        let pid = unsafe { libc::wait4(pid, &mut status, options, &mut rusage) };
+        let pid = Errno::result(pid).map_err(|err| err.into_pyexception(vm))?;
+        let rusage = StructRusage::from(rusage).into_struct_sequence(vm)?;
+        Ok(vm.ctx.new_tuple(vec![
+            vm.ctx.new_int(pid),
+            vm.ctx.new_int(status),
+            rusage.into_object(),
+        ]))
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+    #[pyattr]
+    #[pyclass(module = "os", name = "waitid_result")]
+    #[derive(Debug, PyStructSequence)]
+    struct WaitidResult {
+        si_pid: libc::pid_t,
+        si_uid: libc::uid_t,
+        si_signo: i32,
+        si_status: i32,
+        si_code: i32,
+    }
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+    #[pyimpl(with(PyStructSequence))]
+    impl WaitidResult {}
+
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+    #[pyfunction]
+    fn waitid(idtype: i32, id: libc::id_t, options: i32, vm: &VirtualMachine) -> PyResult {
+        let mut info = unsafe { std::mem::zeroed::<libc::siginfo_t>() };
+        let ret = unsafe { libc::waitid(idtype as libc::idtype_t, id, &mut info, options) };
+        Errno::result(ret).map_err(|err| err.into_pyexception(vm))?;
+        // with WNOHANG and no waitable children, waitid succeeds but leaves
+        // si_pid as 0; CPython returns None for that case
+        let si_pid = unsafe { info.si_pid() };
+        if si_pid == 0 {
+            return Ok(vm.ctx.none());
+        }
+        WaitidResult {
+            si_pid,
+            si_uid: unsafe { info.si_uid() },
+            si_signo: info.si_signo,
+            si_status: unsafe { info.si_status() },
+            si_code: info.si_code,
+        }
+        .into_struct_sequence(vm)
+        .map(|t| t.into_object())
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn wait3(options: i32, vm: &VirtualMachine) -> PyResult {
+        // wait3(status, options, rusage) == wait4(-1, status, options, rusage)
+        wait4(-1, options, vm)
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyattr]
+    #[pyclass(module = "os", name = "statvfs_result")]
+    #[derive(Debug, PyStructSequence)]
+    struct StatvfsResult {
+        f_bsize: u64,
+        f_frsize: u64,
+        f_blocks: u64,
+        f_bfree: u64,
+        f_bavail: u64,
+        f_files: u64,
+        f_ffree: u64,
+        f_favail: u64,
+        f_flag: u64,
+        f_namemax: u64,
+        f_fsid: u64,
+    }
+    #[cfg(not(target_os = "redox"))]
+    #[pyimpl(with(PyStructSequence))]
+    impl StatvfsResult {}
+
+    #[cfg(not(target_os = "redox"))]
+    impl From<libc::statvfs> for StatvfsResult {
+        fn from(st: libc::statvfs) -> Self {
+            Self {
+                f_bsize: st.f_bsize as u64,
+                f_frsize: st.f_frsize as u64,
+                f_blocks: st.f_blocks as u64,
+                f_bfree: st.f_bfree as u64,
+                f_bavail: st.f_bavail as u64,
+                f_files: st.f_files as u64,
+                f_ffree: st.f_ffree as u64,
+                f_favail: st.f_favail as u64,
+                f_flag: st.f_flag as u64,
+                f_namemax: st.f_namemax as u64,
+                f_fsid: st.f_fsid as u64,
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn statvfs(path: PyPathLike, vm: &VirtualMachine) -> PyResult {
+        let path = ffi::CString::new(path.into_bytes())
+            .map_err(|_| vm.new_value_error("embedded null character".to_owned()))?;
+        let mut st = unsafe { std::mem::zeroed::<libc::statvfs>() };
+        let ret = unsafe { libc::statvfs(path.as_ptr(), &mut st) };
+        Errno::result(ret).map_err(|err| err.into_pyexception(vm))?;
+        StatvfsResult::from(st)
+            .into_struct_sequence(vm)
+            .map(|t| t.into_object())
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn fstatvfs(fd: i32, vm: &VirtualMachine) -> PyResult {
+        let mut st = unsafe { std::mem::zeroed::<libc::statvfs>() };
+        let ret = unsafe { libc::fstatvfs(fd, &mut st) };
+        Errno::result(ret).map_err(|err| err.into_pyexception(vm))?;
+        StatvfsResult::from(st)
+            .into_struct_sequence(vm)
+            .map(|t| t.into_object())
+    }
+
+    cfg_if::cfg_if! {
+        if #[cfg(any(target_os = "linux", target_os = "android", target_os = "emscripten"))] {
+            const PATHCONF_NAMES: &[(&str, i32)] = &[
+                ("PC_LINK_MAX", libc::_PC_LINK_MAX),
+                ("PC_MAX_CANON", libc::_PC_MAX_CANON),
+                ("PC_MAX_INPUT", libc::_PC_MAX_INPUT),
+                ("PC_NAME_MAX", libc::_PC_NAME_MAX),
+                ("PC_PATH_MAX", libc::_PC_PATH_MAX),
+                ("PC_PIPE_BUF", libc::_PC_PIPE_BUF),
+                ("PC_CHOWN_RESTRICTED", libc::_PC_CHOWN_RESTRICTED),
+                ("PC_NO_TRUNC", libc::_PC_NO_TRUNC),
+                ("PC_VDISABLE", libc::_PC_VDISABLE),
+                ("PC_SYNC_IO", libc::_PC_SYNC_IO),
+                ("PC_ASYNC_IO", libc::_PC_ASYNC_IO),
+                ("PC_PRIO_IO", libc::_PC_PRIO_IO),
+                ("PC_SOCK_MAXBUF", libc::_PC_SOCK_MAXBUF),
+                ("PC_FILESIZEBITS", libc::_PC_FILESIZEBITS),
+                ("PC_REC_INCR_XFER_SIZE", libc::_PC_REC_INCR_XFER_SIZE),
+                ("PC_REC_MAX_XFER_SIZE", libc::_PC_REC_MAX_XFER_SIZE),
+                ("PC_REC_MIN_XFER_SIZE", libc::_PC_REC_MIN_XFER_SIZE),
+                ("PC_REC_XFER_ALIGN", libc::_PC_REC_XFER_ALIGN),
+                ("PC_ALLOC_SIZE_MIN", libc::_PC_ALLOC_SIZE_MIN),
+                ("PC_SYMLINK_MAX", libc::_PC_SYMLINK_MAX),
+                ("PC_2_SYMLINKS", libc::_PC_2_SYMLINKS),
+            ];
+        } else if #[cfg(target_os = "redox")] {
+            const PATHCONF_NAMES: &[(&str, i32)] = &[];
+        } else {
+            // the base POSIX.1 set; available on all the BSDs
+            const PATHCONF_NAMES: &[(&str, i32)] = &[
+                ("PC_LINK_MAX", libc::_PC_LINK_MAX),
+                ("PC_MAX_CANON", libc::_PC_MAX_CANON),
+                ("PC_MAX_INPUT", libc::_PC_MAX_INPUT),
+                ("PC_NAME_MAX", libc::_PC_NAME_MAX),
+                ("PC_PATH_MAX", libc::_PC_PATH_MAX),
+                ("PC_PIPE_BUF", libc::_PC_PIPE_BUF),
+                ("PC_CHOWN_RESTRICTED", libc::_PC_CHOWN_RESTRICTED),
+                ("PC_NO_TRUNC", libc::_PC_NO_TRUNC),
+                ("PC_VDISABLE", libc::_PC_VDISABLE),
+            ];
+        }
+    }
+
+    #[pyattr]
+    fn pathconf_names(vm: &VirtualMachine) -> PyDictRef {
+        let names = vm.ctx.new_dict();
+        for (name, value) in PATHCONF_NAMES {
+            names
+                .set_item(vm.ctx.new_str(*name), vm.ctx.new_int(*value), vm)
+                .unwrap();
+        }
+        names
+    }
+
+    fn conf_name(
+        name: &Either<PyStrRef, i32>,
+        table: &[(&str, i32)],
+        vm: &VirtualMachine,
+    ) -> PyResult<i32> {
+        match name {
+            Either::A(s) => table
+                .iter()
+                .find(|(n, _)| *n == s.borrow_value())
+                .map(|&(_, v)| v)
+                .ok_or_else(|| vm.new_value_error("unrecognized configuration name".to_owned())),
+            Either::B(v) => Ok(*v),
+        }
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn pathconf(
+        path: PyPathLike,
+        name: Either<PyStrRef, i32>,
+        vm: &VirtualMachine,
+    ) -> PyResult<libc::c_long> {
+        let name = conf_name(&name, PATHCONF_NAMES, vm)?;
+        let path = ffi::CString::new(path.into_bytes())
+            .map_err(|_| vm.new_value_error("embedded null character".to_owned()))?;
+        // a -1 return with errno unchanged means "no limit"; only treat it as
+        // an error when errno was actually set
+        Errno::clear();
+        let limit = unsafe { libc::pathconf(path.as_ptr(), name) };
+        if limit == -1 && nix::errno::errno() != 0 {
+            Err(errno_err(vm))
+        } else {
+            Ok(limit)
+        }
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn fpathconf(fd: i32, name: Either<PyStrRef, i32>, vm: &VirtualMachine) -> PyResult<libc::c_long> {
+        let name = conf_name(&name, PATHCONF_NAMES, vm)?;
+        Errno::clear();
+        let limit = unsafe { libc::fpathconf(fd, name) };
+        if limit == -1 && nix::errno::errno() != 0 {
+            Err(errno_err(vm))
+        } else {
+            Ok(limit)
+        }
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    const SYSCONF_NAMES: &[(&str, i32)] = &[
+        ("SC_ARG_MAX", libc::_SC_ARG_MAX),
+        ("SC_CHILD_MAX", libc::_SC_CHILD_MAX),
+        ("SC_CLK_TCK", libc::_SC_CLK_TCK),
+        ("SC_JOB_CONTROL", libc::_SC_JOB_CONTROL),
+        ("SC_NGROUPS_MAX", libc::_SC_NGROUPS_MAX),
+        ("SC_NPROCESSORS_CONF", libc::_SC_NPROCESSORS_CONF),
+        ("SC_NPROCESSORS_ONLN", libc::_SC_NPROCESSORS_ONLN),
+        ("SC_OPEN_MAX", libc::_SC_OPEN_MAX),
+        ("SC_PAGESIZE", libc::_SC_PAGESIZE),
+        ("SC_PAGE_SIZE", libc::_SC_PAGE_SIZE),
+        ("SC_SAVED_IDS", libc::_SC_SAVED_IDS),
+        ("SC_STREAM_MAX", libc::_SC_STREAM_MAX),
+        ("SC_TZNAME_MAX", libc::_SC_TZNAME_MAX),
+        ("SC_VERSION", libc::_SC_VERSION),
+    ];
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyattr]
+    fn sysconf_names(vm: &VirtualMachine) -> PyDictRef {
+        let names = vm.ctx.new_dict();
+        for (name, value) in SYSCONF_NAMES {
+            names
+                .set_item(vm.ctx.new_str(*name), vm.ctx.new_int(*value), vm)
+                .unwrap();
+        }
+        names
+    }
+
+    #[cfg(not(target_os = "redox"))]
+    #[pyfunction]
+    fn sysconf(name: Either<PyStrRef, i32>, vm: &VirtualMachine) -> PyResult<libc::c_long> {
+        let name = conf_name(&name, SYSCONF_NAMES, vm)?;
+        Errno::clear();
+        let value = unsafe { libc::sysconf(name) };
+        if value == -1 && nix::errno::errno() != 0 {
+            Err(errno_err(vm))
+        } else {
+            Ok(value)
+        }
+    }
+
+    // libc doesn't bind confstr(3) or the _CS_* constants
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    extern "C" {
+        fn confstr(name: libc::c_int, buf: *mut libc::c_char, len: libc::size_t) -> libc::size_t;
+    }
+
+    #[cfg(target_os = "linux")]
+    const CONFSTR_NAMES: &[(&str, i32)] = &[
+        ("CS_PATH", 0),
+        ("CS_GNU_LIBC_VERSION", 2),
+        ("CS_GNU_LIBPTHREAD_VERSION", 3),
+    ];
+    #[cfg(target_os = "macos")]
+    const CONFSTR_NAMES: &[(&str, i32)] = &[("CS_PATH", 1)];
+
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    #[pyattr]
+    fn confstr_names(vm: &VirtualMachine) -> PyDictRef {
+        let names = vm.ctx.new_dict();
+        for (name, value) in CONFSTR_NAMES {
+            names
+                .set_item(vm.ctx.new_str(*name), vm.ctx.new_int(*value), vm)
+                .unwrap();
+        }
+        names
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    #[pyfunction(name = "confstr")]
+    fn confstr_(name: Either<PyStrRef, i32>, vm: &VirtualMachine) -> PyResult<Option<String>> {
+        let name = conf_name(&name, CONFSTR_NAMES, vm)?;
+        Errno::clear();
+        let len = unsafe { confstr(name, std::ptr::null_mut(), 0) };
+        if len == 0 {
+            // 0 with errno untouched means the name is valid but has no value
+            return if nix::errno::errno() != 0 {
+                Err(errno_err(vm))
+            } else {
+                Ok(None)
+            };
+        }
+        let mut buf = vec![0u8; len];
+        unsafe { confstr(name, buf.as_mut_ptr() as *mut libc::c_char, len) };
+        // drop the trailing NUL
+        buf.pop();
+        Ok(Some(String::from_utf8_lossy(&buf).into_owned()))
+    }
+
     #[pyfunction]
     fn kill(pid: i32, sig: isize, vm: &VirtualMachine) -> PyResult<()> {
         {
@@ -2484,9 +4995,16 @@ mod posix {
                     ws_xpixel: 0,
                     ws_ypixel: 0,
                 };
-                unsafe { winsz(fd.unwrap_or(libc::STDOUT_FILENO), &mut w) }
-                    .map_err(|err| err.into_pyexception(vm))?;
-                (w.ws_col.into(), w.ws_row.into())
+                match unsafe { winsz(fd.unwrap_or(libc::STDOUT_FILENO), &mut w) } {
+                    Ok(_) => (w.ws_col.into(), w.ws_row.into()),
+                    Err(err @ nix::Error::Sys(nix::errno::Errno::ENOTTY)) => {
+                        match super::terminal_size_from_env() {
+                            Some(size) => size,
+                            None => return Err(err.into_pyexception(vm)),
+                        }
+                    }
+                    Err(err) => return Err(err.into_pyexception(vm)),
+                }
             }
         };
         super::_os::PyTerminalSize { columns, lines }.into_struct_sequence(vm)
@@ -2517,16 +5035,66 @@ mod posix {
 
     #[pyfunction]
     fn dup(fd: i32, vm: &VirtualMachine) -> PyResult<i32> {
-        let fd = nix::unistd::dup(fd).map_err(|e| e.into_pyexception(vm))?;
-        raw_set_inheritable(fd, false).map(|()| fd).map_err(|e| {
+        // dup(2) and the fcntl(2) underneath set_inheritable are both
+        // retried on EINTR, per PEP 475
+        let fd = loop {
+            match nix::unistd::dup(fd) {
+                Err(nix::Error::Sys(Errno::EINTR)) => continue,
+                res => break res,
+            }
+        }
+        .map_err(|e| e.into_pyexception(vm))?;
+        loop {
+            match raw_set_inheritable(fd, false) {
+                Err(nix::Error::Sys(Errno::EINTR)) => continue,
+                res => break res,
+            }
+        }
+        .map(|()| fd)
+        .map_err(|e| {
             let _ = nix::unistd::close(fd);
             e.into_pyexception(vm)
         })
     }
 
+    #[derive(FromArgs)]
+    struct Dup2Args {
+        #[pyarg(positional)]
+        fd: i32,
+        #[pyarg(positional)]
+        fd2: i32,
+        #[pyarg(any, default = "true")]
+        inheritable: bool,
+    }
+
+    #[pyfunction]
+    fn dup2(args: Dup2Args, vm: &VirtualMachine) -> PyResult<i32> {
+        let fd2 = loop {
+            match nix::unistd::dup2(args.fd, args.fd2) {
+                Err(nix::Error::Sys(Errno::EINTR)) => continue,
+                res => break res,
+            }
+        }
+        .map_err(|e| e.into_pyexception(vm))?;
+        // unlike dup, the new fd defaults to being inheritable
+        if !args.inheritable {
+            loop {
+                match raw_set_inheritable(fd2, false) {
+                    Err(nix::Error::Sys(Errno::EINTR)) => continue,
+                    res => break res,
+                }
+            }
+            .map_err(|e| {
+                let _ = nix::unistd::close(fd2);
+                e.into_pyexception(vm)
+            })?;
+        }
+        Ok(fd2)
+    }
+
     pub(super) fn support_funcs(vm: &VirtualMachine) -> Vec<SupportFunc> {
         vec![
-            SupportFunc::new(vm, "chmod", chmod, Some(false), Some(false), Some(false)),
+            SupportFunc::new(vm, "chmod", chmod, Some(false), Some(false), Some(true)),
             #[cfg(not(target_os = "redox"))]
             SupportFunc::new(vm, "chroot", chroot, Some(false), None, None),
             #[cfg(not(target_os = "redox"))]
@@ -2536,6 +5104,32 @@ mod posix {
             #[cfg(not(target_os = "redox"))]
             SupportFunc::new(vm, "fchown", fchown, Some(true), None, Some(true)),
             SupportFunc::new(vm, "umask", umask, Some(false), Some(false), Some(false)),
+            #[cfg(not(target_os = "redox"))]
+            SupportFunc::new(vm, "statvfs", statvfs, Some(false), None, None),
+            #[cfg(not(target_os = "redox"))]
+            SupportFunc::new(
+                vm,
+                "mkfifo",
+                mkfifo,
+                Some(false),
+                Some(cfg!(not(any(target_os = "macos", target_os = "ios")))),
+                None,
+            ),
+            #[cfg(target_os = "linux")]
+            SupportFunc::new(vm, "copy_file_range", copy_file_range, Some(true), None, None),
+            SupportFunc::new(vm, "fchmod", fchmod, Some(true), None, None),
+            SupportFunc::new(vm, "tcgetpgrp", tcgetpgrp, Some(true), None, None),
+            SupportFunc::new(vm, "tcsetpgrp", tcsetpgrp, Some(true), None, None),
+            #[cfg(not(target_os = "redox"))]
+            SupportFunc::new(vm, "fchdir", fchdir, Some(true), None, None),
+            #[cfg(target_os = "linux")]
+            SupportFunc::new(vm, "getxattr", getxattr, Some(true), None, Some(true)),
+            #[cfg(target_os = "linux")]
+            SupportFunc::new(vm, "setxattr", setxattr, Some(true), None, Some(true)),
+            #[cfg(target_os = "linux")]
+            SupportFunc::new(vm, "listxattr", listxattr, Some(true), None, Some(true)),
+            #[cfg(target_os = "linux")]
+            SupportFunc::new(vm, "removexattr", removexattr, Some(true), None, Some(true)),
             SupportFunc::new(vm, "execv", execv, None, None, None),
         ]
     }
@@ -2714,6 +5308,116 @@ mod nt {
         body().map_err(|err| err.into_pyexception(vm))
     }
 
+    // minimal winternl.h declarations so we can open a file relative to a
+    // directory handle; winapi doesn't bind NtCreateFile
+    mod ntfile {
+        use winapi::shared::ntdef::{HANDLE, LONG, PVOID, ULONG, USHORT, WCHAR};
+
+        pub type NtStatus = LONG;
+        pub const FILE_OPEN: ULONG = 1;
+
+        #[repr(C)]
+        pub struct UnicodeString {
+            pub length: USHORT,
+            pub maximum_length: USHORT,
+            pub buffer: *mut WCHAR,
+        }
+
+        #[repr(C)]
+        pub struct ObjectAttributes {
+            pub length: ULONG,
+            pub root_directory: HANDLE,
+            pub object_name: *mut UnicodeString,
+            pub attributes: ULONG,
+            pub security_descriptor: PVOID,
+            pub security_quality_of_service: PVOID,
+        }
+
+        #[repr(C)]
+        pub struct IoStatusBlock {
+            pub status: NtStatus,
+            pub information: usize,
+        }
+
+        extern "system" {
+            pub fn NtCreateFile(
+                file_handle: *mut HANDLE,
+                desired_access: ULONG,
+                object_attributes: *mut ObjectAttributes,
+                io_status_block: *mut IoStatusBlock,
+                allocation_size: PVOID,
+                file_attributes: ULONG,
+                share_access: ULONG,
+                create_disposition: ULONG,
+                create_options: ULONG,
+                ea_buffer: PVOID,
+                ea_length: ULONG,
+            ) -> NtStatus;
+        }
+    }
+
+    /// fstatat() equivalent: stat `path` relative to the directory handle
+    /// `dir_fd` by opening it with NtCreateFile and a RootDirectory.
+    pub(super) fn stat_at(
+        dir_fd: i64,
+        path: &Path,
+        follow_symlinks: bool,
+    ) -> io::Result<fs::Metadata> {
+        use std::os::windows::ffi::OsStrExt;
+        use std::os::windows::io::FromRawHandle;
+        use winapi::um::winnt;
+
+        let mut name: Vec<u16> = path.as_os_str().encode_wide().collect();
+        let byte_len = name.len() * std::mem::size_of::<u16>();
+        let mut object_name = ntfile::UnicodeString {
+            length: byte_len as _,
+            maximum_length: byte_len as _,
+            buffer: name.as_mut_ptr(),
+        };
+        let mut attributes = ntfile::ObjectAttributes {
+            length: std::mem::size_of::<ntfile::ObjectAttributes>() as _,
+            root_directory: dir_fd as RawHandle,
+            object_name: &mut object_name,
+            attributes: 0,
+            security_descriptor: std::ptr::null_mut(),
+            security_quality_of_service: std::ptr::null_mut(),
+        };
+        let mut iosb: ntfile::IoStatusBlock = unsafe { std::mem::zeroed() };
+
+        // FILE_OPEN_FOR_BACKUP_INTENT lets us open directories as well;
+        // FILE_OPEN_REPARSE_POINT is the NoFollowSymlink of the NT layer
+        let mut create_options = winnt::FILE_OPEN_FOR_BACKUP_INTENT;
+        if !follow_symlinks {
+            create_options |= winnt::FILE_OPEN_REPARSE_POINT;
+        }
+
+        let mut handle = std::ptr::null_mut();
+        let status = unsafe {
+            ntfile::NtCreateFile(
+                &mut handle,
+                winnt::FILE_READ_ATTRIBUTES | winnt::SYNCHRONIZE,
+                &mut attributes,
+                &mut iosb,
+                std::ptr::null_mut(),
+                0,
+                winnt::FILE_SHARE_READ | winnt::FILE_SHARE_WRITE | winnt::FILE_SHARE_DELETE,
+                ntfile::FILE_OPEN,
+                create_options,
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        if status < 0 {
+            return Err(io::Error::new(
+                ErrorKind::Other,
+                format!("NtCreateFile failed with status {:#x}", status),
+            ));
+        }
+        // File takes ownership of the handle and closes it for us
+        let file = unsafe { File::from_raw_handle(handle) };
+        file.metadata()
+    }
+
     #[pyfunction]
     fn set_inheritable(fd: i64, inheritable: bool, vm: &VirtualMachine) -> PyResult<()> {
         #[cfg(windows)]
@@ -2735,6 +5439,55 @@ mod nt {
         }
     }
 
+    #[pyfunction]
+    fn get_inheritable(fd: i64, vm: &VirtualMachine) -> PyResult<bool> {
+        use winapi::um::{handleapi, winbase};
+        let mut flags = 0;
+        let ret = unsafe { handleapi::GetHandleInformation(fd as RawHandle, &mut flags) };
+        if ret == 0 {
+            Err(errno_err(vm))
+        } else {
+            Ok(flags & winbase::HANDLE_FLAG_INHERIT != 0)
+        }
+    }
+
+    #[pyfunction]
+    fn pipe(vm: &VirtualMachine) -> PyResult<(i32, i32)> {
+        use std::ptr::null_mut;
+        use winapi::um::{handleapi, namedpipeapi};
+        let mut read = null_mut();
+        let mut write = null_mut();
+        // a null security-attributes pointer leaves both ends non-inheritable,
+        // which is what os.pipe promises
+        let ret = unsafe { namedpipeapi::CreatePipe(&mut read, &mut write, null_mut(), 0) };
+        if ret == 0 {
+            return Err(errno_err(vm));
+        }
+        // hand the HANDLEs over to the C runtime so the caller gets a pair of
+        // regular fds; the CRT owns (and eventually closes) the handles
+        let rfd =
+            unsafe { suppress_iph!(libc::open_osfhandle(read as libc::intptr_t, libc::O_RDONLY)) };
+        let wfd =
+            unsafe { suppress_iph!(libc::open_osfhandle(write as libc::intptr_t, libc::O_WRONLY)) };
+        if rfd == -1 || wfd == -1 {
+            let err = errno_err(vm);
+            unsafe {
+                if rfd == -1 {
+                    handleapi::CloseHandle(read);
+                } else {
+                    suppress_iph!(libc::close(rfd));
+                }
+                if wfd == -1 {
+                    handleapi::CloseHandle(write);
+                } else {
+                    suppress_iph!(libc::close(wfd));
+                }
+            }
+            return Err(err);
+        }
+        Ok((rfd, wfd))
+    }
+
     #[pyattr]
     fn environ(vm: &VirtualMachine) -> PyDictRef {
         let environ = vm.ctx.new_dict();
@@ -2817,6 +5570,59 @@ mod nt {
         }
     }
 
+    // windows wait statuses carry the exit code in the high byte
+    #[pyfunction]
+    fn waitstatus_to_exitcode(status: i32) -> i32 {
+        status >> 8
+    }
+
+    #[pyfunction]
+    fn getloadavg(vm: &VirtualMachine) -> PyResult<(f64, f64, f64)> {
+        use winapi::um::pdh;
+        let unobtainable =
+            || vm.new_os_error("Load averages are unobtainable".to_owned());
+
+        // "\System\Processor Queue Length" is the closest thing the
+        // performance counters have to a unix load average; it's an
+        // instantaneous value, so report it for all three intervals
+        let counter_path: Vec<u16> = "\\System\\Processor Queue Length"
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+
+        let mut query = std::ptr::null_mut();
+        if unsafe { pdh::PdhOpenQueryW(std::ptr::null(), 0, &mut query) } != 0 {
+            return Err(unobtainable());
+        }
+        let res = (|| {
+            let mut counter = std::ptr::null_mut();
+            if unsafe { pdh::PdhAddEnglishCounterW(query, counter_path.as_ptr(), 0, &mut counter) }
+                != 0
+            {
+                return Err(unobtainable());
+            }
+            if unsafe { pdh::PdhCollectQueryData(query) } != 0 {
+                return Err(unobtainable());
+            }
+            let mut value: pdh::PDH_FMT_COUNTERVALUE = unsafe { std::mem::zeroed() };
+            let ret = unsafe {
+                pdh::PdhGetFormattedCounterValue(
+                    counter,
+                    pdh::PDH_FMT_DOUBLE,
+                    std::ptr::null_mut(),
+                    &mut value,
+                )
+            };
+            if ret != 0 {
+                return Err(unobtainable());
+            }
+            let load = unsafe { *value.u.doubleValue() };
+            Ok((load, load, load))
+        })();
+        unsafe { pdh::PdhCloseQuery(query) };
+        res
+    }
+
     #[pyfunction]
     fn get_terminal_size(fd: OptionalArg<i32>, vm: &VirtualMachine) -> PyResult<PyTupleRef> {
         let (columns, lines) = {
@@ -2838,13 +5644,19 @@ mod nt {
                 let mut csbi = wincon::CONSOLE_SCREEN_BUFFER_INFO::default();
                 let ret = unsafe { wincon::GetConsoleScreenBufferInfo(h, &mut csbi) };
                 if ret == 0 {
-                    return Err(errno_err(vm));
+                    // no console attached (e.g. output redirected) -- fall back
+                    // to the COLUMNS/LINES environment variables
+                    match super::terminal_size_from_env() {
+                        Some(size) => size,
+                        None => return Err(errno_err(vm)),
+                    }
+                } else {
+                    let w = csbi.srWindow;
+                    (
+                        (w.Right - w.Left + 1) as usize,
+                        (w.Bottom - w.Top + 1) as usize,
+                    )
                 }
-                let w = csbi.srWindow;
-                (
-                    (w.Right - w.Left + 1) as usize,
-                    (w.Bottom - w.Top + 1) as usize,
-                )
             }
         };
         super::_os::PyTerminalSize { columns, lines }.into_struct_sequence(vm)
@@ -2929,6 +5741,46 @@ mod nt {
         }
     }
 
+    #[pyfunction]
+    fn times(vm: &VirtualMachine) -> PyResult {
+        use winapi::shared::minwindef::FILETIME;
+        use winapi::um::processthreadsapi::{GetCurrentProcess, GetProcessTimes};
+
+        let mut create = FILETIME::default();
+        let mut exit = FILETIME::default();
+        let mut kernel = FILETIME::default();
+        let mut user = FILETIME::default();
+        let ret = unsafe {
+            GetProcessTimes(
+                GetCurrentProcess(),
+                &mut create,
+                &mut exit,
+                &mut kernel,
+                &mut user,
+            )
+        };
+        if ret == 0 {
+            return Err(errno_err(vm));
+        }
+        // FILETIME is in 100ns units
+        let to_seconds =
+            |ft: &FILETIME| ((ft.dwHighDateTime as u64) << 32 | ft.dwLowDateTime as u64) as f64 * 1e-7;
+        super::_os::TimesResult {
+            user: to_seconds(&user),
+            system: to_seconds(&kernel),
+            children_user: 0.0,
+            children_system: 0.0,
+            elapsed: 0.0,
+        }
+        .into_struct_sequence(vm)
+        .map(|t| t.into_object())
+    }
+
+    #[pyfunction]
+    fn nice(_increment: i32, vm: &VirtualMachine) -> PyResult<i32> {
+        Err(vm.new_not_implemented_error("nice(2) is not available on Windows".to_owned()))
+    }
+
     pub(super) fn support_funcs(_vm: &VirtualMachine) -> Vec<SupportFunc> {
         Vec::new()
     }
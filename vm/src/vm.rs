@@ -119,6 +119,15 @@ pub struct PyGlobalState {
     pub thread_count: AtomicCell<usize>,
     pub hash_secret: HashSecret,
     pub atexit_funcs: PyMutex<Vec<(PyObjectRef, FuncArgs)>>,
+    pub atfork_hooks: PyMutex<AtForkHooks>,
+}
+
+/// Callbacks registered through os.register_at_fork(), run around fork()
+#[derive(Debug, Default)]
+pub struct AtForkHooks {
+    pub before: Vec<PyObjectRef>,
+    pub after_in_child: Vec<PyObjectRef>,
+    pub after_in_parent: Vec<PyObjectRef>,
 }
 
 pub const NSIG: usize = 64;
@@ -286,6 +295,7 @@ impl VirtualMachine {
                 thread_count: AtomicCell::new(0),
                 hash_secret,
                 atexit_funcs: PyMutex::default(),
+                atfork_hooks: PyMutex::default(),
             }),
             initialized: false,
         };